@@ -0,0 +1,227 @@
+-- Extension for UUID generation
+CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+-- Trigram matching for search suggestions
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+-- Vector similarity for content embeddings
+CREATE EXTENSION IF NOT EXISTS vector;
+
+-- Users table
+CREATE TABLE users (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    google_sub VARCHAR(255) UNIQUE NOT NULL, -- Google account subject identifier
+    email VARCHAR(255) UNIQUE NOT NULL,
+    display_name VARCHAR(255) NOT NULL,
+    avatar_url TEXT,
+    is_admin BOOLEAN NOT NULL DEFAULT FALSE, -- global (site-wide) admin
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Refresh tokens (stored hashed); rotations of one login share a family_id
+CREATE TABLE refresh_tokens (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL,
+    token_hash VARCHAR(64) UNIQUE NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Streams (communities/groups)
+CREATE TABLE streams (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    owner_id UUID NOT NULL REFERENCES users(id),
+    is_private BOOLEAN NOT NULL DEFAULT TRUE,
+    settings JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Stream membership and permissions
+CREATE TABLE stream_members (
+    stream_id UUID NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(50) NOT NULL, -- 'admin', 'creator', 'viewer' (ownership is streams.owner_id)
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (stream_id, user_id)
+);
+
+-- Invite links granting a role in a stream
+CREATE TABLE invites (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    code VARCHAR(32) UNIQUE NOT NULL,
+    stream_id UUID NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    role VARCHAR(50) NOT NULL, -- 'creator', 'viewer'
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ,
+    max_uses INTEGER,
+    uses_count INTEGER NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Audit trail of who joined through each invite
+CREATE TABLE invite_uses (
+    invite_id UUID NOT NULL REFERENCES invites(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (invite_id, user_id)
+);
+
+-- Videos table (core functionality)
+CREATE TABLE videos (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    stream_id UUID NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    uploader_id UUID NOT NULL REFERENCES users(id),
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    duration_seconds INTEGER,
+    size_bytes BIGINT,
+    storage_path VARCHAR(255),
+    thumbnail_path VARCHAR(255),
+    hls_master_path VARCHAR(255), -- written by the transcoding stage
+    dash_manifest_path VARCHAR(255),
+    processing_index INTEGER NOT NULL DEFAULT 0, -- -1 duplicate, 0 queued, 1-4 pipeline stage, 5 ready
+    processing_progress FLOAT DEFAULT 0,
+    views_count INTEGER DEFAULT 0,
+    embedding VECTOR(512), -- written by the analysis stage
+    embedding_model VARCHAR(100), -- model name/version that produced the embedding
+    pov_group_id UUID, -- videos of the same moment from different players share a group
+    pov_offset_seconds DOUBLE PRECISION, -- alignment of this video within its POV group
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    search_vector TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', COALESCE(title, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(description, '')), 'B')
+    ) STORED
+);
+
+-- Transcoded renditions (the HLS/DASH quality ladder) for each video
+CREATE TABLE video_renditions (
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    name VARCHAR(16) NOT NULL, -- e.g. '720p'
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    bitrate_kbps INTEGER NOT NULL,
+    playlist_path VARCHAR(255) NOT NULL,
+    PRIMARY KEY (video_id, name)
+);
+
+-- One row per pipeline stage run for a video; workers pick up 'queued' rows
+CREATE TABLE processing_jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    stage VARCHAR(50) NOT NULL, -- 'duplicate_detection', 'transcoding', 'transcription', 'analysis'
+    status VARCHAR(20) NOT NULL DEFAULT 'queued', -- 'awaiting_upload', 'queued', 'running', 'completed', 'failed'
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+-- Earlier videos a flagged duplicate (processing_index = -1) was matched against
+CREATE TABLE duplicate_matches (
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    original_video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    similarity REAL NOT NULL,
+    method VARCHAR(20) NOT NULL, -- 'perceptual_hash', 'embedding'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (video_id, original_video_id)
+);
+
+-- Auto-generated highlight clips cut from a video by the analysis stage
+CREATE TABLE trimmed_clips (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    start_seconds DOUBLE PRECISION NOT NULL,
+    end_seconds DOUBLE PRECISION NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    confidence REAL NOT NULL, -- 0.0-1.0
+    storage_path VARCHAR(255) NOT NULL, -- under processed-videos/{video_id}/
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Moments and chapter boundaries marked by the analysis stage
+CREATE TABLE highlights (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL, -- 'highlight', 'chapter'
+    at_seconds DOUBLE PRECISION NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Speech-to-text output from the transcription stage
+CREATE TABLE transcripts (
+    video_id UUID PRIMARY KEY REFERENCES videos(id) ON DELETE CASCADE,
+    language VARCHAR(16) NOT NULL DEFAULT 'en',
+    full_text TEXT NOT NULL,
+    segments JSONB NOT NULL DEFAULT '[]', -- [{ start, end, text }]
+    keywords JSONB NOT NULL DEFAULT '[]', -- [{ time, keyword }]
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    search_vector TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', full_text)) STORED
+);
+
+-- Normalized search terms and how often they've been searched (feeds suggestions)
+CREATE TABLE search_queries (
+    query TEXT PRIMARY KEY,
+    search_count BIGINT NOT NULL DEFAULT 1,
+    last_searched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per user who likes a video
+CREATE TABLE video_likes (
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (video_id, user_id)
+);
+
+-- Public share links for videos
+CREATE TABLE shares (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    code VARCHAR(32) UNIQUE NOT NULL,
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ,
+    max_views INTEGER,
+    views_count INTEGER NOT NULL DEFAULT 0,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Object storage keys waiting to be deleted (keys ending in '/' are prefixes)
+CREATE TABLE storage_deletions (
+    id BIGSERIAL PRIMARY KEY,
+    object_key TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Basic indexes
+CREATE INDEX idx_streams_owner_id ON streams(owner_id);
+CREATE INDEX idx_streams_created_at_id ON streams(created_at DESC, id DESC);
+CREATE INDEX idx_stream_members_user_id ON stream_members(user_id);
+CREATE INDEX idx_stream_members_joined_at ON stream_members(stream_id, joined_at, user_id);
+CREATE INDEX idx_invites_stream_id ON invites(stream_id);
+CREATE INDEX idx_video_likes_user_id ON video_likes(user_id);
+CREATE INDEX idx_shares_video_id ON shares(video_id);
+CREATE INDEX idx_shares_created_by ON shares(created_by);
+CREATE INDEX idx_processing_jobs_video_id ON processing_jobs(video_id, created_at DESC);
+CREATE INDEX idx_processing_jobs_status ON processing_jobs(status, created_at);
+CREATE INDEX idx_trimmed_clips_video_id ON trimmed_clips(video_id, start_seconds);
+CREATE INDEX idx_highlights_video_id ON highlights(video_id, at_seconds);
+CREATE INDEX idx_refresh_tokens_family_id ON refresh_tokens(family_id);
+CREATE INDEX idx_videos_stream_id ON videos(stream_id, created_at DESC, id DESC);
+CREATE INDEX idx_videos_uploader_id ON videos(uploader_id);
+CREATE INDEX idx_videos_processing_index ON videos(processing_index);
+CREATE INDEX idx_videos_search_vector ON videos USING GIN (search_vector);
+CREATE INDEX idx_transcripts_search_vector ON transcripts USING GIN (search_vector);
+CREATE INDEX idx_videos_pov_group_id ON videos(pov_group_id) WHERE pov_group_id IS NOT NULL;
+CREATE INDEX idx_videos_embedding ON videos USING hnsw (embedding vector_cosine_ops);
+CREATE INDEX idx_videos_title_trgm ON videos USING GIN (title gin_trgm_ops);
+CREATE INDEX idx_search_queries_query_trgm ON search_queries USING GIN (query gin_trgm_ops);
\ No newline at end of file
@@ -0,0 +1,43 @@
+-- Publish processing progress on the `processing_events` channel so the API
+-- can push it to clients over SSE. Payload:
+-- { video_id, processing_index, progress, failed }
+
+CREATE OR REPLACE FUNCTION notify_video_progress() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('processing_events', json_build_object(
+        'video_id', NEW.id,
+        'processing_index', NEW.processing_index,
+        'progress', NEW.processing_progress,
+        'failed', FALSE
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER videos_progress_notify
+    AFTER UPDATE OF processing_index, processing_progress ON videos
+    FOR EACH ROW
+    WHEN (OLD.processing_index IS DISTINCT FROM NEW.processing_index
+          OR OLD.processing_progress IS DISTINCT FROM NEW.processing_progress)
+    EXECUTE FUNCTION notify_video_progress();
+
+-- A failed job stops the pipeline without changing processing_index
+CREATE OR REPLACE FUNCTION notify_job_failed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('processing_events', json_build_object(
+        'video_id', v.id,
+        'processing_index', v.processing_index,
+        'progress', v.processing_progress,
+        'failed', TRUE
+    )::text)
+    FROM videos v
+    WHERE v.id = NEW.video_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER processing_jobs_failed_notify
+    AFTER INSERT OR UPDATE OF status ON processing_jobs
+    FOR EACH ROW
+    WHEN (NEW.status = 'failed')
+    EXECUTE FUNCTION notify_job_failed();
@@ -0,0 +1,3 @@
+-- Filled in by POST /videos/:id/upload-complete from the stored object
+ALTER TABLE videos ADD COLUMN content_type VARCHAR(100);
+ALTER TABLE videos ADD COLUMN uploaded_at TIMESTAMPTZ;
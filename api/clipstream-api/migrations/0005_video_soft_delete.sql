@@ -0,0 +1,4 @@
+-- Soft-deleted videos stay restorable until the sweep removes them
+ALTER TABLE videos ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_videos_deleted_at ON videos (deleted_at) WHERE deleted_at IS NOT NULL;
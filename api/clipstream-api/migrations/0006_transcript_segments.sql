@@ -0,0 +1,37 @@
+-- One row per transcript segment so a single video's transcript can be
+-- searched by text. Kept in sync with transcripts.segments by trigger, so
+-- the workers keep writing the JSONB column as before.
+
+CREATE TABLE transcript_segments (
+    video_id UUID NOT NULL REFERENCES transcripts(video_id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    start_seconds DOUBLE PRECISION NOT NULL,
+    end_seconds DOUBLE PRECISION NOT NULL,
+    text TEXT NOT NULL,
+    search_vector TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', text)) STORED,
+    PRIMARY KEY (video_id, position)
+);
+
+CREATE INDEX idx_transcript_segments_search ON transcript_segments USING GIN (search_vector);
+
+CREATE OR REPLACE FUNCTION sync_transcript_segments() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM transcript_segments WHERE video_id = NEW.video_id;
+    INSERT INTO transcript_segments (video_id, position, start_seconds, end_seconds, text)
+    SELECT NEW.video_id, s.position - 1, (s.segment->>'start')::float8, (s.segment->>'end')::float8,
+           COALESCE(s.segment->>'text', '')
+    FROM jsonb_array_elements(NEW.segments) WITH ORDINALITY AS s(segment, position);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER transcripts_sync_segments
+    AFTER INSERT OR UPDATE OF segments ON transcripts
+    FOR EACH ROW
+    EXECUTE FUNCTION sync_transcript_segments();
+
+-- Backfill transcripts written before this migration
+INSERT INTO transcript_segments (video_id, position, start_seconds, end_seconds, text)
+SELECT t.video_id, s.position - 1, (s.segment->>'start')::float8, (s.segment->>'end')::float8,
+       COALESCE(s.segment->>'text', '')
+FROM transcripts t, jsonb_array_elements(t.segments) WITH ORDINALITY AS s(segment, position);
@@ -0,0 +1,72 @@
+-- Per-stream webhook called when a video finishes or fails processing.
+-- Deliveries are queued by trigger, so every path that updates a video (API,
+-- workers, admin tools) is covered, and sent by the API's delivery task.
+
+CREATE TABLE stream_webhooks (
+    stream_id UUID PRIMARY KEY REFERENCES streams(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL, -- HMAC-SHA256 key for X-Clipstream-Signature
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    stream_id UUID NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    video_id UUID NOT NULL,
+    event VARCHAR(50) NOT NULL, -- 'video.ready', 'video.duplicate', 'video.failed'
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- 'pending', 'delivered', 'failed'
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_status_code INTEGER,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_status ON webhook_deliveries (status, created_at DESC);
+
+CREATE OR REPLACE FUNCTION queue_webhook_delivery(p_video_id UUID, p_event TEXT) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO webhook_deliveries (stream_id, video_id, event, payload)
+    SELECT v.stream_id, v.id, p_event, json_build_object(
+        'event', p_event,
+        'video_id', v.id,
+        'stream_id', v.stream_id,
+        'title', v.title,
+        'processing_index', v.processing_index,
+        'occurred_at', NOW()
+    )
+    FROM videos v
+    JOIN stream_webhooks w ON w.stream_id = v.stream_id
+    WHERE v.id = p_video_id;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION queue_video_finished_webhook() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM queue_webhook_delivery(NEW.id, CASE NEW.processing_index WHEN 5 THEN 'video.ready' ELSE 'video.duplicate' END);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER videos_finished_webhook
+    AFTER UPDATE OF processing_index ON videos
+    FOR EACH ROW
+    WHEN (OLD.processing_index IS DISTINCT FROM NEW.processing_index AND NEW.processing_index IN (5, -1))
+    EXECUTE FUNCTION queue_video_finished_webhook();
+
+CREATE OR REPLACE FUNCTION queue_job_failed_webhook() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM queue_webhook_delivery(NEW.video_id, 'video.failed');
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER processing_jobs_failed_webhook
+    AFTER INSERT OR UPDATE OF status ON processing_jobs
+    FOR EACH ROW
+    WHEN (NEW.status = 'failed')
+    EXECUTE FUNCTION queue_job_failed_webhook();
@@ -0,0 +1,4 @@
+-- Bumped on every metadata edit; clients send it back in If-Match so
+-- concurrent edits fail instead of silently overwriting each other
+ALTER TABLE streams ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE videos ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+-- Invites addressed to someone who doesn't have an account yet
+ALTER TABLE invites ADD COLUMN email VARCHAR(255);
+
+CREATE INDEX idx_invites_email ON invites (lower(email)) WHERE email IS NOT NULL;
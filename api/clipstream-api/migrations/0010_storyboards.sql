@@ -0,0 +1,12 @@
+-- Scrubbing preview sprites, written by the transcoding stage alongside the
+-- sprite image at `storyboards/{video_id}.jpg`. Tiles are laid out left to
+-- right, top to bottom, one every `interval_seconds` of playback.
+CREATE TABLE storyboards (
+    video_id UUID PRIMARY KEY REFERENCES videos(id) ON DELETE CASCADE,
+    tile_width INTEGER NOT NULL CHECK (tile_width > 0),
+    tile_height INTEGER NOT NULL CHECK (tile_height > 0),
+    columns INTEGER NOT NULL CHECK (columns > 0),
+    tile_count INTEGER NOT NULL CHECK (tile_count > 0),
+    interval_seconds DOUBLE PRECISION NOT NULL CHECK (interval_seconds > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Outcome of emailing an invite link; NULL until the send finishes
+ALTER TABLE invites ADD COLUMN email_delivered BOOLEAN;
+ALTER TABLE invites ADD COLUMN email_error TEXT;
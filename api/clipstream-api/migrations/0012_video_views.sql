@@ -0,0 +1,9 @@
+-- Last time each user played each video (one row per pair), for "recently viewed"
+CREATE TABLE video_views (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    video_id UUID NOT NULL REFERENCES videos(id) ON DELETE CASCADE,
+    viewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, video_id)
+);
+
+CREATE INDEX idx_video_views_user_viewed_at ON video_views(user_id, viewed_at DESC);
@@ -0,0 +1,4 @@
+-- Trigram indexes for searching streams by name/description and members by name
+CREATE INDEX idx_streams_name_trgm ON streams USING GIN (name gin_trgm_ops);
+CREATE INDEX idx_streams_description_trgm ON streams USING GIN (description gin_trgm_ops);
+CREATE INDEX idx_users_display_name_trgm ON users USING GIN (display_name gin_trgm_ops);
@@ -0,0 +1,9 @@
+-- One row per successful view of a share link. Viewer IPs are stored only
+-- as a keyed hash (scoped to the share), enough to estimate unique viewers.
+CREATE TABLE share_views (
+    share_id UUID NOT NULL REFERENCES shares(id) ON DELETE CASCADE,
+    ip_hash VARCHAR(32) NOT NULL,
+    viewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_share_views_share_id ON share_views(share_id, viewed_at DESC);
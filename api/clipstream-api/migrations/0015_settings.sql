@@ -0,0 +1,6 @@
+-- Small runtime switches that admins change without a deploy, keyed by name
+CREATE TABLE settings (
+    key VARCHAR(64) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+-- Per-stream storage quota set by a global admin; NULL falls back to STREAM_STORAGE_QUOTA_BYTES
+ALTER TABLE streams ADD COLUMN storage_quota_bytes BIGINT CHECK (storage_quota_bytes > 0);
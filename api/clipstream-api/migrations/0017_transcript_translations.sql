@@ -0,0 +1,10 @@
+-- `transcripts.language` holds the language the transcription stage detected
+-- in the audio. Machine translations of the same segments (same timings) are
+-- stored per language alongside it.
+CREATE TABLE transcript_translations (
+    video_id UUID NOT NULL REFERENCES transcripts(video_id) ON DELETE CASCADE,
+    language VARCHAR(16) NOT NULL,
+    segments JSONB NOT NULL DEFAULT '[]', -- [{ start, end, text }]
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (video_id, language)
+);
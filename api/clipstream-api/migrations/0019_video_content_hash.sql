@@ -0,0 +1,6 @@
+-- SHA-256 of the original file as reported by the uploading client, so a
+-- re-upload of the same file to a stream can be answered with the existing video
+ALTER TABLE videos ADD COLUMN content_hash VARCHAR(64);
+
+CREATE INDEX idx_videos_stream_content_hash ON videos(stream_id, content_hash)
+    WHERE content_hash IS NOT NULL AND deleted_at IS NULL;
@@ -0,0 +1,5 @@
+-- Videos a stream admin has pinned to the top of the stream's video list
+ALTER TABLE videos ADD COLUMN pinned_at TIMESTAMPTZ;
+
+CREATE INDEX idx_videos_stream_pinned ON videos(stream_id)
+    WHERE pinned_at IS NOT NULL AND deleted_at IS NULL;
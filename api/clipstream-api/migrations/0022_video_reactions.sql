@@ -0,0 +1,9 @@
+-- Likes become one kind of reaction; existing likes keep their timestamps
+ALTER TABLE video_likes RENAME TO video_reactions;
+ALTER TABLE video_reactions ADD COLUMN reaction VARCHAR(16) NOT NULL DEFAULT 'like';
+ALTER TABLE video_reactions ALTER COLUMN reaction DROP DEFAULT;
+
+ALTER TABLE video_reactions DROP CONSTRAINT video_likes_pkey;
+ALTER TABLE video_reactions ADD PRIMARY KEY (video_id, reaction, user_id);
+
+ALTER INDEX idx_video_likes_user_id RENAME TO idx_video_reactions_user_id;
@@ -0,0 +1,4 @@
+-- Trending scans each engagement table by time window before joining videos
+CREATE INDEX idx_video_views_viewed_at ON video_views(viewed_at);
+CREATE INDEX idx_video_reactions_likes_created_at ON video_reactions(created_at) WHERE reaction = 'like';
+CREATE INDEX idx_shares_created_at ON shares(created_at);
@@ -0,0 +1,7 @@
+-- RFC 6381 codec string for each rendition (e.g. 'avc1.64001f,mp4a.40.2'),
+-- written by the transcoding stage for the DASH manifest
+ALTER TABLE video_renditions ADD COLUMN codecs VARCHAR(64);
+
+-- The DASH manifest is generated per request from video_renditions, so a
+-- stored manifest path was never written or read
+ALTER TABLE videos DROP COLUMN dash_manifest_path;
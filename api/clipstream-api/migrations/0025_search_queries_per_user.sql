@@ -0,0 +1,6 @@
+-- Suggestions only replay the caller's own searches, so history is kept per
+-- user. Existing rows can't be attributed to anyone and are dropped.
+DELETE FROM search_queries;
+ALTER TABLE search_queries DROP CONSTRAINT search_queries_pkey;
+ALTER TABLE search_queries ADD COLUMN user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE search_queries ADD PRIMARY KEY (user_id, query);
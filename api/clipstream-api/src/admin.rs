@@ -1,41 +1,534 @@
 use axum::{
+    extract::{Path, State},
+    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+use uuid::Uuid;
 
-#[derive(Serialize)]
-struct SystemResponse {
-    message: String,
-    purpose: String,
+use crate::{
+    audit::{self, Action, Target},
+    auth::AdminUser,
+    error::AppError,
+    pagination::{self, Page},
+    processing,
+    query::{Validate, ValidQuery},
+    videos,
+};
+
+const LARGEST_STREAMS_LIMIT: i64 = 10;
+// Failed jobs that have already been retried this many times are left alone
+const MAX_PROCESSING_RETRIES: i32 = 3;
+const STORAGE_STATS_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_PREVIEW_LIMIT: i64 = 20;
+const MAX_PREVIEW_LIMIT: i64 = 100;
+
+// The storage aggregates scan every video, so they're computed at most once per TTL
+static STORAGE_STATS_CACHE: Mutex<Option<(Instant, StorageStats)>> = Mutex::const_new(None);
+
+#[derive(Clone, Serialize, sqlx::FromRow)]
+struct StreamUsage {
+    id: Uuid,
+    name: String,
+    video_count: i64,
+    total_bytes: i64,
 }
 
-async fn get_storage_stats() -> Json<SystemResponse> {
-    Json(SystemResponse {
-        message: "Storage usage statistics endpoint".to_string(),
-        purpose: "Get detailed storage usage, space allocation, and video count statistics (Admin only)".to_string(),
-    })
+#[derive(Clone, Serialize)]
+struct StorageStats {
+    total_bytes: i64,
+    /// Soft-deleted videos still held in storage until the sweep purges them.
+    deleted_bytes: i64,
+    video_count: i64,
+    duplicate_count: i64,
+    stream_count: i64,
+    largest_streams: Vec<StreamUsage>,
+    #[serde(with = "time::serde::rfc3339")]
+    generated_at: OffsetDateTime,
 }
 
-async fn list_flagged_duplicates() -> Json<SystemResponse> {
-    Json(SystemResponse {
-        message: "List flagged duplicates endpoint".to_string(),
-        purpose: "List all videos flagged as duplicates for admin review and management (Admin only)".to_string(),
+async fn compute_storage_stats(pool: &PgPool) -> Result<StorageStats, sqlx::Error> {
+    // Soft-deleted videos are left out of the totals, like every other count,
+    // and reported on their own since their files aren't gone yet
+    let (total_bytes, deleted_bytes, video_count, duplicate_count, stream_count) =
+        sqlx::query_as::<_, (i64, i64, i64, i64, i64)>(
+            "SELECT COALESCE(SUM(size_bytes) FILTER (WHERE deleted_at IS NULL), 0)::bigint,
+                    COALESCE(SUM(size_bytes) FILTER (WHERE deleted_at IS NOT NULL), 0)::bigint,
+                    COUNT(*) FILTER (WHERE deleted_at IS NULL),
+                    COUNT(*) FILTER (WHERE deleted_at IS NULL AND processing_index = -1),
+                    (SELECT COUNT(*) FROM streams)
+             FROM videos",
+        )
+        .fetch_one(pool)
+        .await?;
+
+    let largest_streams = sqlx::query_as::<_, StreamUsage>(
+        "SELECT s.id, s.name, COUNT(v.id) AS video_count,
+                COALESCE(SUM(v.size_bytes), 0)::bigint AS total_bytes
+         FROM streams s
+         JOIN videos v ON v.stream_id = s.id AND v.deleted_at IS NULL
+         GROUP BY s.id
+         ORDER BY total_bytes DESC, s.id
+         LIMIT $1",
+    )
+    .bind(LARGEST_STREAMS_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(StorageStats {
+        total_bytes,
+        deleted_bytes,
+        video_count,
+        duplicate_count,
+        stream_count,
+        largest_streams,
+        generated_at: OffsetDateTime::now_utc(),
     })
 }
 
-async fn retry_failed_processing() -> Json<SystemResponse> {
-    Json(SystemResponse {
-        message: "Retry failed processing jobs endpoint".to_string(),
-        purpose: "Retry failed video processing jobs and reset their status for reprocessing (Admin only)".to_string(),
-    })
+async fn get_storage_stats(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+) -> Result<Json<StorageStats>, AppError> {
+    // Holding the lock while computing stops concurrent requests recomputing too
+    let mut cache = STORAGE_STATS_CACHE.lock().await;
+    if let Some((computed_at, stats)) = cache.as_ref()
+        && computed_at.elapsed() < STORAGE_STATS_TTL
+    {
+        return Ok(Json(stats.clone()));
+    }
+
+    let stats = compute_storage_stats(&pool).await?;
+    *cache = Some((Instant::now(), stats.clone()));
+    Ok(Json(stats))
+}
+
+#[derive(Serialize, Deserialize)]
+struct MatchedOriginal {
+    original_video_id: Uuid,
+    title: String,
+    similarity: f32,
+    method: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct FlaggedDuplicate {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    uploader_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    matches: sqlx::types::Json<Vec<MatchedOriginal>>,
+}
+
+#[derive(Deserialize)]
+struct DuplicatesQuery {
+    stream_id: Option<Uuid>,
+    /// Only videos with a match at least this similar, 0-1.
+    min_score: Option<f32>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+impl Validate for DuplicatesQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.min_score.is_some_and(|score| !(0.0..=1.0).contains(&score)) {
+            return Err(AppError::Validation("min_score must be between 0 and 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+async fn list_flagged_duplicates(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+    ValidQuery(query): ValidQuery<DuplicatesQuery>,
+) -> Result<Json<Page<FlaggedDuplicate>>, AppError> {
+    let limit = pagination::page_limit(query.limit);
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    // Matches are aggregated per video in the same query. With `min_score`
+    // only the qualifying matches are listed, and videos without one drop out.
+    let duplicates = sqlx::query_as::<_, FlaggedDuplicate>(
+        "SELECT v.id, v.stream_id, v.title, v.uploader_id, v.created_at,
+                COALESCE(dm.matches, '[]'::json) AS matches
+         FROM videos v
+         LEFT JOIN LATERAL (
+             SELECT json_agg(json_build_object(
+                        'original_video_id', o.id,
+                        'title', o.title,
+                        'similarity', d.similarity,
+                        'method', d.method
+                    ) ORDER BY d.similarity DESC) AS matches
+             FROM duplicate_matches d
+             JOIN videos o ON o.id = d.original_video_id
+             WHERE d.video_id = v.id
+               AND ($6::real IS NULL OR d.similarity >= $6)
+         ) dm ON TRUE
+         WHERE v.processing_index = $1
+           AND v.deleted_at IS NULL
+           AND ($5::uuid IS NULL OR v.stream_id = $5)
+           AND ($6::real IS NULL OR dm.matches IS NOT NULL)
+           AND ($2::timestamptz IS NULL OR (v.created_at, v.id) < ($2, $3))
+         ORDER BY v.created_at DESC, v.id DESC
+         LIMIT $4",
+    )
+    .bind(processing::DUPLICATE)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .bind(query.stream_id)
+    .bind(query.min_score)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(pagination::into_page(duplicates, limit, |duplicate| (duplicate.created_at, duplicate.id))))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Resolution {
+    /// The video really is a duplicate: delete it.
+    Delete,
+    /// False positive: clear the flag and mark the video ready.
+    Clear,
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+    action: Resolution,
+}
+
+#[derive(Serialize)]
+struct ResolvedDuplicate {
+    video_id: Uuid,
+    resolution: &'static str,
+}
+
+async fn resolve_duplicate(
+    State(pool): State<PgPool>,
+    admin: AdminUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ResolveRequest>,
+) -> Result<Json<ResolvedDuplicate>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let video = sqlx::query_as::<_, (i32, Option<String>, Option<String>)>(
+        "SELECT processing_index, storage_path, thumbnail_path FROM videos WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    // Repeating a resolution that already happened succeeds without changes
+    match (request.action, video) {
+        (Resolution::Delete, None) => Ok(Json(ResolvedDuplicate {
+            video_id: id,
+            resolution: "deleted",
+        })),
+        (Resolution::Delete, Some((processing::DUPLICATE, storage_path, thumbnail_path))) => {
+            sqlx::query("DELETE FROM videos WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            let details = serde_json::json!({ "resolution": "deleted" });
+            audit::record(&mut *tx, admin.0.user_id, Action::DuplicateResolve, Target::Video(id), details).await?;
+            tx.commit().await?;
+
+            videos::purge_video_objects(&pool, id, storage_path, thumbnail_path).await;
+            Ok(Json(ResolvedDuplicate {
+                video_id: id,
+                resolution: "deleted",
+            }))
+        }
+        (Resolution::Clear, Some((processing::DUPLICATE, _, _))) => {
+            sqlx::query("UPDATE videos SET processing_index = $2, updated_at = NOW() WHERE id = $1")
+                .bind(id)
+                .bind(processing::READY)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM duplicate_matches WHERE video_id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            let details = serde_json::json!({ "resolution": "cleared" });
+            audit::record(&mut *tx, admin.0.user_id, Action::DuplicateResolve, Target::Video(id), details).await?;
+            tx.commit().await?;
+
+            Ok(Json(ResolvedDuplicate {
+                video_id: id,
+                resolution: "cleared",
+            }))
+        }
+        (Resolution::Clear, Some((processing::READY, _, _))) => Ok(Json(ResolvedDuplicate {
+            video_id: id,
+            resolution: "cleared",
+        })),
+        (Resolution::Clear, None) => Err(AppError::NotFound),
+        (_, Some(_)) => Err(AppError::Conflict("Video is not flagged as a duplicate".to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+struct SimilarityPreviewQuery {
+    video_id: Uuid,
+    /// Cosine similarity a neighbour needs to be listed, 0-1.
+    threshold: f64,
+    limit: Option<i64>,
+}
+
+impl Validate for SimilarityPreviewQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err(AppError::Validation("threshold must be between 0 and 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct SimilarityNeighbour {
+    id: Uuid,
+    stream_id: Uuid,
+    stream_name: String,
+    title: String,
+    similarity: f64,
+    same_stream: bool,
+}
+
+#[derive(Serialize)]
+struct SimilarityPreview {
+    video_id: Uuid,
+    stream_id: Uuid,
+    threshold: f64,
+    neighbours: Vec<SimilarityNeighbour>,
+}
+
+async fn preview_similarity(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+    ValidQuery(query): ValidQuery<SimilarityPreviewQuery>,
+) -> Result<Json<SimilarityPreview>, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_PREVIEW_LIMIT).clamp(1, MAX_PREVIEW_LIMIT);
+
+    let (stream_id, embedding) = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT stream_id, embedding::text FROM videos WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(query.video_id)
+    .fetch_one(&pool)
+    .await?;
+    let embedding = embedding.ok_or_else(|| AppError::Conflict("Embedding not ready".to_string()))?;
+
+    // Same neighbour search as `GET /videos/:id/similar` without the membership
+    // join. The cutoff is applied outside the ordered subquery so the HNSW
+    // index still drives the search.
+    let neighbours = sqlx::query_as::<_, SimilarityNeighbour>(
+        "SELECT n.id, n.stream_id, s.name AS stream_name, n.title, n.similarity,
+                n.stream_id = $3 AS same_stream
+         FROM (
+             SELECT v.id, v.stream_id, v.title, 1 - (v.embedding <=> $1::vector) AS similarity
+             FROM videos v
+             WHERE v.id <> $2 AND v.embedding IS NOT NULL AND v.deleted_at IS NULL
+             ORDER BY v.embedding <=> $1::vector
+             LIMIT $4
+         ) n
+         JOIN streams s ON s.id = n.stream_id
+         WHERE n.similarity >= $5
+         ORDER BY n.similarity DESC",
+    )
+    .bind(embedding)
+    .bind(query.video_id)
+    .bind(stream_id)
+    .bind(limit)
+    .bind(query.threshold)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(SimilarityPreview {
+        video_id: query.video_id,
+        stream_id,
+        threshold: query.threshold,
+        neighbours,
+    }))
+}
+
+#[derive(Deserialize)]
+struct RetryRequest {
+    video_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct ExhaustedJob {
+    job_id: Uuid,
+    video_id: Uuid,
+    stage: String,
+    retry_count: i32,
+}
+
+#[derive(Serialize)]
+struct RetryResult {
+    requeued: u64,
+    skipped: Vec<ExhaustedJob>,
+}
+
+async fn retry_failed_processing(
+    State(pool): State<PgPool>,
+    admin: AdminUser,
+    request: Option<Json<RetryRequest>>,
+) -> Result<Json<RetryResult>, AppError> {
+    let video_ids = request.and_then(|Json(request)| request.video_ids);
+
+    let mut tx = pool.begin().await?;
+
+    let requeued = sqlx::query(
+        "UPDATE processing_jobs
+         SET status = 'queued', retry_count = retry_count + 1, error = NULL,
+             started_at = NULL, finished_at = NULL
+         WHERE status = 'failed' AND retry_count < $1
+           AND ($2::uuid[] IS NULL OR video_id = ANY($2))",
+    )
+    .bind(MAX_PROCESSING_RETRIES)
+    .bind(&video_ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let skipped = sqlx::query_as::<_, ExhaustedJob>(
+        "SELECT id AS job_id, video_id, stage, retry_count
+         FROM processing_jobs
+         WHERE status = 'failed' AND retry_count >= $1
+           AND ($2::uuid[] IS NULL OR video_id = ANY($2))
+         ORDER BY created_at",
+    )
+    .bind(MAX_PROCESSING_RETRIES)
+    .bind(&video_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let details = serde_json::json!({ "video_ids": video_ids, "requeued": requeued, "skipped": skipped.len() });
+    audit::record(&mut *tx, admin.0.user_id, Action::ProcessingRetry, Target::None, details).await?;
+    tx.commit().await?;
+
+    Ok(Json(RetryResult { requeued, skipped }))
+}
+
+#[derive(Deserialize)]
+struct ReprocessRequest {
+    /// Drop the previous run's outputs (renditions, transcript, embedding,
+    /// clips, thumbnails, ...) before starting over.
+    #[serde(default)]
+    clear_derived: bool,
+}
+
+#[derive(Serialize)]
+struct ReprocessResult {
+    video_id: Uuid,
+    job_id: Uuid,
+    cleared_derived: bool,
+}
+
+// Runs the whole pipeline again from the first stage, e.g. after a worker upgrade
+async fn reprocess_video(
+    State(pool): State<PgPool>,
+    admin: AdminUser,
+    Path(id): Path<Uuid>,
+    request: Option<Json<ReprocessRequest>>,
+) -> Result<(StatusCode, Json<ReprocessResult>), AppError> {
+    let clear_derived = request.is_some_and(|Json(request)| request.clear_derived);
+
+    let mut tx = pool.begin().await?;
+
+    // The row lock serializes concurrent reprocess calls for one video, so
+    // the active-job check below can't race
+    let (uploaded, thumbnail_path) = sqlx::query_as::<_, (bool, Option<String>)>(
+        "SELECT uploaded_at IS NOT NULL, thumbnail_path FROM videos
+         WHERE id = $1 AND deleted_at IS NULL
+         FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if !uploaded {
+        return Err(AppError::Conflict("Video has not been uploaded".to_string()));
+    }
+
+    let active = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+             SELECT 1 FROM processing_jobs WHERE video_id = $1 AND status IN ('queued', 'running')
+         )",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if active {
+        return Err(AppError::Conflict("Video already has an active processing job".to_string()));
+    }
+
+    if clear_derived {
+        for table in ["video_renditions", "duplicate_matches", "trimmed_clips", "highlights", "transcripts", "storyboards"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE video_id = $1", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query(
+            "UPDATE videos
+             SET thumbnail_path = NULL, hls_master_path = NULL,
+                 embedding = NULL, embedding_model = NULL, pov_group_id = NULL, pov_offset_seconds = NULL
+             WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE videos SET processing_index = $2, processing_progress = 0, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(id)
+    .bind(processing::QUEUED)
+    .execute(&mut *tx)
+    .await?;
+    let job_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO processing_jobs (video_id, stage, status) VALUES ($1, $2, 'queued') RETURNING id",
+    )
+    .bind(id)
+    .bind(processing::STAGES[0])
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let details = serde_json::json!({ "job_id": job_id, "clear_derived": clear_derived });
+    audit::record(&mut *tx, admin.0.user_id, Action::VideoReprocess, Target::Video(id), details).await?;
+    tx.commit().await?;
+
+    // Only once the rows are gone, so nothing points at a deleted object
+    if clear_derived {
+        videos::delete_video_objects(&pool, id, videos::derived_storage_keys(id, thumbnail_path)).await;
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ReprocessResult {
+            video_id: id,
+            job_id,
+            cleared_derived: clear_derived,
+        }),
+    ))
 }
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/system/storage", get(get_storage_stats))
         .route("/admin/duplicates", get(list_flagged_duplicates))
+        .route("/admin/duplicates/:id/resolve", post(resolve_duplicate))
+        .route("/admin/similarity/preview", get(preview_similarity))
         .route("/admin/processing/retry", post(retry_failed_processing))
+        .route("/admin/videos/:id/reprocess", post(reprocess_video))
 }
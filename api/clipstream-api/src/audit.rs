@@ -0,0 +1,146 @@
+use axum::{extract::State, response::Json, routing::get, Router};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    pagination::{self, Page},
+    query::{Validate, ValidQuery},
+};
+
+/// An admin action worth recording.
+#[derive(Clone, Copy)]
+pub(crate) enum Action {
+    ProcessingRetry,
+    DuplicateResolve,
+    VideoReprocess,
+    MaintenanceSet,
+    StreamQuotaSet,
+}
+
+const ACTIONS: [Action; 5] = [
+    Action::ProcessingRetry,
+    Action::DuplicateResolve,
+    Action::VideoReprocess,
+    Action::MaintenanceSet,
+    Action::StreamQuotaSet,
+];
+
+impl Action {
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::ProcessingRetry => "processing.retry",
+            Action::DuplicateResolve => "duplicate.resolve",
+            Action::VideoReprocess => "video.reprocess",
+            Action::MaintenanceSet => "maintenance.set",
+            Action::StreamQuotaSet => "stream.quota_set",
+        }
+    }
+}
+
+/// What an action was applied to; system-wide actions have none.
+pub(crate) enum Target {
+    None,
+    Video(Uuid),
+    Stream(Uuid),
+}
+
+/// Record `action` by `actor_id`. Pass the action's own transaction so the
+/// entry is committed, or rolled back, together with it.
+pub(crate) async fn record(
+    executor: impl PgExecutor<'_>,
+    actor_id: Uuid,
+    action: Action,
+    target: Target,
+    details: serde_json::Value,
+) -> Result<(), AppError> {
+    let (target_type, target_id) = match target {
+        Target::None => (None, None),
+        Target::Video(id) => (Some("video"), Some(id)),
+        Target::Stream(id) => (Some("stream"), Some(id)),
+    };
+
+    sqlx::query(
+        "INSERT INTO audit_log (actor_id, action, target_type, target_id, details)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(actor_id)
+    .bind(action.as_str())
+    .bind(target_type)
+    .bind(target_id)
+    .bind(details)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct AuditEntry {
+    id: Uuid,
+    /// `null` once the admin's account is deleted.
+    actor_id: Option<Uuid>,
+    actor_name: Option<String>,
+    action: String,
+    target_type: Option<String>,
+    target_id: Option<Uuid>,
+    details: serde_json::Value,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    actor_id: Option<Uuid>,
+    action: Option<String>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+impl Validate for AuditQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(action) = &self.action
+            && !ACTIONS.iter().any(|known| known.as_str() == action)
+        {
+            let names: Vec<_> = ACTIONS.iter().map(|known| known.as_str()).collect();
+            return Err(AppError::Validation(format!("action must be one of {}", names.join(", "))));
+        }
+        Ok(())
+    }
+}
+
+async fn list_audit_log(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+    ValidQuery(query): ValidQuery<AuditQuery>,
+) -> Result<Json<Page<AuditEntry>>, AppError> {
+    let limit = pagination::page_limit(query.limit);
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    let entries = sqlx::query_as::<_, AuditEntry>(
+        "SELECT a.id, a.actor_id, u.display_name AS actor_name, a.action, a.target_type, a.target_id,
+                a.details, a.created_at
+         FROM audit_log a
+         LEFT JOIN users u ON u.id = a.actor_id
+         WHERE ($1::uuid IS NULL OR a.actor_id = $1)
+           AND ($2::text IS NULL OR a.action = $2)
+           AND ($3::timestamptz IS NULL OR (a.created_at, a.id) < ($3, $4))
+         ORDER BY a.created_at DESC, a.id DESC
+         LIMIT $5",
+    )
+    .bind(query.actor_id)
+    .bind(&query.action)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(pagination::into_page(entries, limit, |entry| (entry.created_at, entry.id))))
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new().route("/admin/audit", get(list_audit_log))
+}
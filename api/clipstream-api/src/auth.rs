@@ -1,38 +1,367 @@
 use axum::{
+    async_trait,
+    extract::{FromRequestParts, State},
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap},
     response::Json,
     routing::{get, post},
     Router,
 };
-use serde::Serialize;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::{config, error::AppError};
+
+pub mod jwt;
+pub mod refresh;
+
+const GOOGLE_CERTS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+const GOOGLE_ISSUERS: [&str; 2] = ["accounts.google.com", "https://accounts.google.com"];
+const APP_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
 
-#[derive(Serialize)]
-struct AuthResponse {
-    message: String,
-    purpose: String,
+// Used when Google doesn't send a usable Cache-Control header
+const DEFAULT_JWKS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+static JWKS_CACHE: RwLock<Option<CachedJwks>> = RwLock::const_new(None);
+
+struct CachedJwks {
+    keys: JwkSet,
+    expires_at: Instant,
 }
 
-async fn verify_token() -> Json<AuthResponse> {
-    Json(AuthResponse {
-        message: "Authentication endpoint".to_string(),
-        purpose: "Verify Google ID token and create/update user, returns app JWT for subsequent API calls".to_string(),
-    })
+/// Profile claims carried by a Google ID token. `aud`, `iss` and `exp` are
+/// checked during decoding and not kept.
+#[derive(Debug, Deserialize)]
+pub struct GoogleClaims {
+    pub sub: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub picture: Option<String>,
 }
 
-async fn get_user() -> Json<AuthResponse> {
-    Json(AuthResponse {
-        message: "User info endpoint".to_string(),
-        purpose: "Get current user info including profile data and statistics (requires Bearer token)".to_string(),
-    })
+#[derive(Deserialize, ToSchema)]
+struct VerifyRequest {
+    id_token: String,
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct UserSummary {
+    id: Uuid,
+    email: String,
+    name: String,
+    avatar: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct VerifyResponse {
+    user: UserSummary,
+    app_token: String,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+    refresh_token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct RefreshResponse {
+    app_token: String,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+    refresh_token: String,
 }
 
-async fn refresh_token() -> Json<AuthResponse> {
-    Json(AuthResponse {
-        message: "Token refresh endpoint".to_string(),
-        purpose: "Refresh app JWT token to extend authentication session".to_string(),
+/// The caller identified by a valid `Authorization: Bearer <app token>` header.
+pub struct AuthUser {
+    pub user_id: Uuid,
+    pub email: String,
+    pub is_admin: bool,
+}
+
+/// An authenticated caller who is a global admin; anyone else is rejected
+/// with `403`.
+pub struct AdminUser(pub AuthUser);
+
+// The raw token from an `Authorization: Bearer ...` header
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// The user id in a valid app token, without checking the account still
+/// exists. Middleware uses this to key per-user state cheaply; handlers
+/// should take [`AuthUser`] instead.
+pub fn bearer_user_id(headers: &HeaderMap) -> Option<Uuid> {
+    let token = bearer_token(headers)?;
+    jwt::decode_token(token).ok().map(|claims| claims.sub)
+}
+
+#[async_trait]
+impl FromRequestParts<PgPool> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, pool: &PgPool) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers).ok_or(AppError::Unauthorized)?;
+        let claims = jwt::decode_token(token).map_err(|_| AppError::Unauthorized)?;
+
+        let (email, is_admin) = sqlx::query_as::<_, (String, bool)>("SELECT email, is_admin FROM users WHERE id = $1")
+            .bind(claims.sub)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        Ok(AuthUser {
+            user_id: claims.sub,
+            email,
+            is_admin,
+        })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<PgPool> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, pool: &PgPool) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, pool).await?;
+        if !user.is_admin {
+            return Err(AppError::Forbidden);
+        }
+        Ok(AdminUser(user))
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct UserProfile {
+    id: Uuid,
+    email: String,
+    display_name: String,
+    avatar_url: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    owned_streams: i64,
+    uploaded_videos: i64,
+}
+
+/// Verify a Google ID token against the given key set: RS256 signature,
+/// audience, issuer and expiry.
+pub fn verify_google_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    client_id: &str,
+) -> Result<GoogleClaims, jsonwebtoken::errors::Error> {
+    let header = decode_header(id_token)?;
+    let kid = header
+        .kid
+        .ok_or(jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+    let jwk = jwks
+        .find(&kid)
+        .ok_or(jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+    let key = DecodingKey::from_jwk(jwk)?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&GOOGLE_ISSUERS);
+
+    Ok(decode::<GoogleClaims>(id_token, &key, &validation)?.claims)
+}
+
+// Parse `max-age` out of a Cache-Control header value
+fn cache_max_age(cache_control: &str) -> Option<Duration> {
+    cache_control
+        .split(',')
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|secs| secs.parse().ok())
+        .map(Duration::from_secs)
+}
+
+async fn fetch_google_jwks() -> Result<CachedJwks, reqwest::Error> {
+    let response = HTTP_CLIENT
+        .get(GOOGLE_CERTS_URL)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let max_age = response
+        .headers()
+        .get(reqwest::header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(cache_max_age)
+        .unwrap_or(DEFAULT_JWKS_MAX_AGE);
+
+    Ok(CachedJwks {
+        keys: response.json().await?,
+        expires_at: Instant::now() + max_age,
     })
 }
 
+// Return Google's signing keys, refetching once the cached copy has expired
+async fn google_jwks() -> Result<JwkSet, reqwest::Error> {
+    if let Some(cached) = JWKS_CACHE.read().await.as_ref()
+        && cached.expires_at > Instant::now()
+    {
+        return Ok(cached.keys.clone());
+    }
+
+    let mut cache = JWKS_CACHE.write().await;
+    // Another request may have refreshed the cache while we waited for the lock
+    if let Some(cached) = cache.as_ref()
+        && cached.expires_at > Instant::now()
+    {
+        return Ok(cached.keys.clone());
+    }
+
+    let fresh = fetch_google_jwks().await?;
+    let keys = fresh.keys.clone();
+    *cache = Some(fresh);
+    Ok(keys)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify",
+    tag = "auth",
+    request_body = VerifyRequest,
+    responses(
+        (status = 200, description = "Signed in; app and refresh tokens issued", body = VerifyResponse),
+        (status = 401, description = "Google ID token is invalid", body = ErrorBody),
+    )
+)]
+async fn verify_token(
+    State(pool): State<PgPool>,
+    Json(request): Json<VerifyRequest>,
+) -> Result<Json<VerifyResponse>, AppError> {
+    let client_id = config::get().google_client_id.as_deref().ok_or(AppError::Internal)?;
+    let jwks = google_jwks().await.map_err(|_| AppError::Internal)?;
+
+    let claims = verify_google_token(&request.id_token, &jwks, client_id)
+        .map_err(|_| AppError::Unauthorized)?;
+
+    let mut tx = pool.begin().await?;
+
+    let display_name = claims.name.as_deref().unwrap_or(&claims.email);
+    let user = sqlx::query_as::<_, UserSummary>(
+        "INSERT INTO users (google_sub, email, display_name, avatar_url)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (google_sub) DO UPDATE
+         SET email = EXCLUDED.email,
+             display_name = EXCLUDED.display_name,
+             avatar_url = EXCLUDED.avatar_url,
+             updated_at = NOW()
+         RETURNING id, email, display_name AS name, avatar_url AS avatar",
+    )
+    .bind(&claims.sub)
+    .bind(&claims.email)
+    .bind(display_name)
+    .bind(&claims.picture)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Each sign-in starts a new refresh token family
+    let refresh_token = refresh::issue_refresh_token(&mut tx, user.id, None).await?;
+    tx.commit().await?;
+
+    let expires_at = OffsetDateTime::now_utc() + APP_TOKEN_TTL;
+    let app_token = jwt::issue_token(user.id, APP_TOKEN_TTL).map_err(|_| AppError::Internal)?;
+
+    Ok(Json(VerifyResponse {
+        user,
+        app_token,
+        expires_at,
+        refresh_token,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/user",
+    tag = "auth",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The signed-in user's profile", body = UserProfile),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 404, description = "Account no longer exists", body = ErrorBody),
+    )
+)]
+async fn get_user(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+) -> Result<Json<UserProfile>, AppError> {
+    let profile = sqlx::query_as::<_, UserProfile>(
+        "SELECT u.id, u.email, u.display_name, u.avatar_url, u.created_at,
+                (SELECT COUNT(*) FROM streams s WHERE s.owner_id = u.id) AS owned_streams,
+                (SELECT COUNT(*) FROM videos v WHERE v.uploader_id = u.id) AS uploaded_videos
+         FROM users u
+         WHERE u.id = $1",
+    )
+    .bind(user.user_id)
+    .fetch_optional(&pool)
+    .await?
+    // The account may have been deleted after the token was issued
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(profile))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "Token rotated", body = RefreshResponse),
+        (status = 401, description = "Refresh token is invalid, expired or reused", body = ErrorBody),
+    )
+)]
+async fn refresh_token(
+    State(pool): State<PgPool>,
+    Json(request): Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse>, AppError> {
+    let mut tx = pool.begin().await?;
+    let rotated = refresh::rotate_refresh_token(&mut tx, &request.refresh_token).await;
+
+    // Commit on reuse too, so the family revocation is persisted
+    if matches!(rotated, Ok(_) | Err(refresh::RefreshError::Reused)) {
+        tx.commit().await?;
+    }
+
+    let (user_id, refresh_token) = rotated.map_err(|err| match err {
+        refresh::RefreshError::Database(err) => AppError::Database(err),
+        refresh::RefreshError::Invalid | refresh::RefreshError::Reused => AppError::Unauthorized,
+    })?;
+
+    let expires_at = OffsetDateTime::now_utc() + APP_TOKEN_TTL;
+    let app_token = jwt::issue_token(user_id, APP_TOKEN_TTL).map_err(|_| AppError::Internal)?;
+
+    Ok(Json(RefreshResponse {
+        app_token,
+        expires_at,
+        refresh_token,
+    }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(verify_token, get_user, refresh_token),
+    components(schemas(VerifyRequest, VerifyResponse, UserSummary, RefreshRequest, RefreshResponse, UserProfile))
+)]
+pub(crate) struct AuthApi;
+
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/auth/verify", post(verify_token))
@@ -0,0 +1,92 @@
+use jsonwebtoken::{decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::OnceLock, time::Duration};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+// Tolerated clock difference between us and clients when checking `exp`
+const CLOCK_SKEW_LEEWAY_SECS: u64 = 5;
+
+static KEYS: OnceLock<Keys> = OnceLock::new();
+
+struct Keys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+/// Claims carried by our own application JWTs.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppClaims {
+    pub sub: Uuid,
+    pub iat: i64,
+    pub exp: i64,
+    pub jti: Uuid,
+}
+
+#[derive(Debug)]
+pub enum JwtError {
+    /// `JWT_SECRET` was not set (or empty) when the server started.
+    MissingSecret,
+    Expired,
+    Invalid(jsonwebtoken::errors::Error),
+}
+
+impl fmt::Display for JwtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JwtError::MissingSecret => write!(f, "JWT_SECRET environment variable is not set"),
+            JwtError::Expired => write!(f, "token has expired"),
+            JwtError::Invalid(err) => write!(f, "invalid token: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for JwtError {}
+
+impl From<jsonwebtoken::errors::Error> for JwtError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        match err.kind() {
+            ErrorKind::ExpiredSignature => JwtError::Expired,
+            _ => JwtError::Invalid(err),
+        }
+    }
+}
+
+/// Install the signing secret (`JWT_SECRET`). Called once at startup so a
+/// missing secret stops the server instead of failing individual requests.
+pub fn init(secret: &str) -> Result<(), JwtError> {
+    if secret.is_empty() {
+        return Err(JwtError::MissingSecret);
+    }
+
+    let _ = KEYS.set(Keys {
+        encoding: EncodingKey::from_secret(secret.as_bytes()),
+        decoding: DecodingKey::from_secret(secret.as_bytes()),
+    });
+    Ok(())
+}
+
+fn keys() -> Result<&'static Keys, JwtError> {
+    KEYS.get().ok_or(JwtError::MissingSecret)
+}
+
+/// Issue an HS256 app token for `user_id` that expires after `ttl`.
+pub fn issue_token(user_id: Uuid, ttl: Duration) -> Result<String, JwtError> {
+    let issued_at = OffsetDateTime::now_utc();
+    let claims = AppClaims {
+        sub: user_id,
+        iat: issued_at.unix_timestamp(),
+        exp: (issued_at + ttl).unix_timestamp(),
+        jti: Uuid::new_v4(),
+    };
+
+    Ok(encode(&Header::new(Algorithm::HS256), &claims, &keys()?.encoding)?)
+}
+
+/// Decode and validate an app token, allowing a few seconds of clock skew on `exp`.
+pub fn decode_token(token: &str) -> Result<AppClaims, JwtError> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.leeway = CLOCK_SKEW_LEEWAY_SECS;
+
+    Ok(decode::<AppClaims>(token, &keys()?.decoding, &validation)?.claims)
+}
@@ -0,0 +1,198 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
+use std::{fmt, time::Duration};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub const REFRESH_TOKEN_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A stored refresh token. Only the SHA-256 of the token is persisted; every
+/// rotation of one login shares the same `family_id`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub expires_at: OffsetDateTime,
+    pub revoked_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug)]
+pub enum RefreshError {
+    /// Unknown or expired token.
+    Invalid,
+    /// An already-rotated token was presented again; its family has been revoked.
+    Reused,
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for RefreshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RefreshError::Invalid => write!(f, "invalid refresh token"),
+            RefreshError::Reused => write!(f, "refresh token reuse detected"),
+            RefreshError::Database(err) => write!(f, "database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RefreshError {}
+
+impl From<sqlx::Error> for RefreshError {
+    fn from(err: sqlx::Error) -> Self {
+        RefreshError::Database(err)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Store a new refresh token for `user_id` and return its plaintext value.
+/// Pass `family_id: None` to start a new family (a fresh login).
+pub async fn issue_refresh_token(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    family_id: Option<Uuid>,
+) -> Result<String, sqlx::Error> {
+    let token = generate_token();
+
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user_id)
+    .bind(family_id.unwrap_or_else(Uuid::new_v4))
+    .bind(hash_token(&token))
+    .bind(OffsetDateTime::now_utc() + REFRESH_TOKEN_TTL)
+    .execute(conn)
+    .await?;
+
+    Ok(token)
+}
+
+/// Exchange a refresh token for a new one in the same family, revoking the
+/// presented token. Presenting a token that was already revoked revokes the
+/// whole family, since it means the token has leaked.
+///
+/// Expected to run inside a transaction; the caller must commit even when
+/// `RefreshError::Reused` is returned so the family revocation sticks.
+pub async fn rotate_refresh_token(
+    conn: &mut PgConnection,
+    presented: &str,
+) -> Result<(Uuid, String), RefreshError> {
+    let stored = sqlx::query_as::<_, RefreshToken>(
+        "SELECT id, user_id, family_id, expires_at, revoked_at
+         FROM refresh_tokens
+         WHERE token_hash = $1
+         FOR UPDATE",
+    )
+    .bind(hash_token(presented))
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(RefreshError::Invalid)?;
+
+    if stored.revoked_at.is_some() {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW()
+             WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(stored.family_id)
+        .execute(&mut *conn)
+        .await?;
+        return Err(RefreshError::Reused);
+    }
+
+    if stored.expires_at <= OffsetDateTime::now_utc() {
+        return Err(RefreshError::Invalid);
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE id = $1")
+        .bind(stored.id)
+        .execute(&mut *conn)
+        .await?;
+
+    let token = issue_refresh_token(conn, stored.user_id, Some(stored.family_id)).await?;
+    Ok((stored.user_id, token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{db_test, user};
+
+    async fn live_tokens(conn: &mut PgConnection, user_id: Uuid) -> i64 {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM refresh_tokens WHERE user_id = $1 AND revoked_at IS NULL")
+            .bind(user_id)
+            .fetch_one(conn)
+            .await
+            .unwrap()
+    }
+
+    db_test! {
+        async fn rotation_replaces_the_token(pool: PgPool) {
+            let mut conn = pool.acquire().await.unwrap();
+            let user_id = user(&mut *conn, "user").await;
+            let first = issue_refresh_token(&mut conn, user_id, None).await.unwrap();
+
+            let (rotated_for, second) = rotate_refresh_token(&mut conn, &first).await.unwrap();
+            assert_eq!(rotated_for, user_id);
+            assert_ne!(second, first);
+            assert_eq!(live_tokens(&mut conn, user_id).await, 1);
+
+            // The replacement is in the same family and rotates in turn
+            let families =
+                sqlx::query_scalar::<_, i64>("SELECT COUNT(DISTINCT family_id) FROM refresh_tokens WHERE user_id = $1")
+                    .bind(user_id)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .unwrap();
+            assert_eq!(families, 1);
+            assert!(rotate_refresh_token(&mut conn, &second).await.is_ok());
+        }
+
+        async fn reusing_a_rotated_token_revokes_the_family(pool: PgPool) {
+            let mut conn = pool.acquire().await.unwrap();
+            let user_id = user(&mut *conn, "user").await;
+            let first = issue_refresh_token(&mut conn, user_id, None).await.unwrap();
+            let other_login = issue_refresh_token(&mut conn, user_id, None).await.unwrap();
+            let (_, second) = rotate_refresh_token(&mut conn, &first).await.unwrap();
+
+            assert!(matches!(rotate_refresh_token(&mut conn, &first).await, Err(RefreshError::Reused)));
+            // The legitimate successor is revoked with it, so it's reported as reused too
+            assert!(matches!(rotate_refresh_token(&mut conn, &second).await, Err(RefreshError::Reused)));
+            // Other logins are separate families and keep working
+            assert_eq!(live_tokens(&mut conn, user_id).await, 1);
+            assert!(rotate_refresh_token(&mut conn, &other_login).await.is_ok());
+        }
+
+        async fn expired_token_is_invalid(pool: PgPool) {
+            let mut conn = pool.acquire().await.unwrap();
+            let user_id = user(&mut *conn, "user").await;
+            let token = issue_refresh_token(&mut conn, user_id, None).await.unwrap();
+            sqlx::query("UPDATE refresh_tokens SET expires_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+
+            assert!(matches!(rotate_refresh_token(&mut conn, &token).await, Err(RefreshError::Invalid)));
+            assert!(matches!(rotate_refresh_token(&mut conn, "not-a-token").await, Err(RefreshError::Invalid)));
+            // Nothing was issued in its place
+            let issued = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM refresh_tokens WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+            assert_eq!(issued, 1);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use rand::{distributions::Alphanumeric, Rng};
+
+// Attempts before giving up on finding an unused code
+pub const MAX_CODE_ATTEMPTS: usize = 5;
+
+/// Generate a random URL-safe code for invite and share links.
+pub fn generate_code(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Whether `err` is a Postgres unique-constraint violation, i.e. a generated
+/// code collided with an existing one and should be regenerated.
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505"))
+}
@@ -0,0 +1,221 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::AppError,
+    pagination::{self, Page, PageQuery},
+    query::ValidQuery,
+    streams::{self, Role, StreamAccess},
+    videos,
+};
+
+const MAX_COMMENT_LEN: usize = 2000;
+
+#[derive(Serialize)]
+struct Author {
+    id: Uuid,
+    display_name: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CommentRow {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+    body: String,
+    timestamp_seconds: Option<f64>,
+    author_id: Uuid,
+    author_name: String,
+    author_avatar_url: Option<String>,
+    created_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct Comment {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+    body: String,
+    /// Seconds into the video the comment refers to.
+    timestamp: Option<f64>,
+    author: Author,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+impl From<CommentRow> for Comment {
+    fn from(row: CommentRow) -> Self {
+        Comment {
+            id: row.id,
+            parent_id: row.parent_id,
+            body: row.body,
+            timestamp: row.timestamp_seconds,
+            author: Author {
+                id: row.author_id,
+                display_name: row.author_name,
+                avatar_url: row.author_avatar_url,
+            },
+            created_at: row.created_at,
+        }
+    }
+}
+
+// Author details are joined in, so a page is one query however many people commented
+const COMMENT_COLUMNS: &str = "c.id, c.parent_id, c.body, c.timestamp_seconds, c.author_id,
+    u.display_name AS author_name, u.avatar_url AS author_avatar_url, c.created_at";
+
+// Comments are open to every member of the video's stream
+async fn require_video_member(pool: &PgPool, video_id: Uuid, user_id: Uuid) -> Result<StreamAccess, AppError> {
+    let stream_id = videos::video_stream_id(pool, video_id).await?;
+    streams::require_stream_role(pool, stream_id, user_id, Role::Viewer).await
+}
+
+// Oldest first, so threads read in order; replies carry their `parent_id`
+async fn list_comments(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<Page<Comment>>, AppError> {
+    require_video_member(&pool, id, user.user_id).await?;
+
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    let rows = sqlx::query_as::<_, CommentRow>(&format!(
+        "SELECT {}
+         FROM comments c
+         JOIN users u ON u.id = c.author_id
+         WHERE c.video_id = $1
+           AND ($2::timestamptz IS NULL OR (c.created_at, c.id) > ($2, $3))
+         ORDER BY c.created_at, c.id
+         LIMIT $4",
+        COMMENT_COLUMNS
+    ))
+    .bind(id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, comment_id)| comment_id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let page = pagination::into_page(rows, limit, |row| (row.created_at, row.id));
+    Ok(Json(Page {
+        items: page.items.into_iter().map(Comment::from).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[derive(Deserialize)]
+struct CreateCommentRequest {
+    body: String,
+    /// Reply to this comment, which must be on the same video.
+    parent_id: Option<Uuid>,
+    /// Seconds into the video.
+    timestamp: Option<f64>,
+}
+
+async fn create_comment(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<Comment>), AppError> {
+    let body = request.body.trim();
+    if body.is_empty() || body.chars().count() > MAX_COMMENT_LEN {
+        return Err(AppError::Validation(format!(
+            "body must be between 1 and {} characters",
+            MAX_COMMENT_LEN
+        )));
+    }
+
+    require_video_member(&pool, id, user.user_id).await?;
+
+    if let Some(timestamp) = request.timestamp {
+        let (duration,) = videos::fetch_video::<(Option<i32>,)>(&pool, id, "duration_seconds").await?;
+        let past_end = duration.is_some_and(|duration| timestamp > f64::from(duration));
+        if !timestamp.is_finite() || timestamp < 0.0 || past_end {
+            return Err(AppError::Validation(
+                "timestamp must be between 0 and the video's duration".to_string(),
+            ));
+        }
+    }
+
+    if let Some(parent_id) = request.parent_id {
+        let same_video = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM comments WHERE id = $1 AND video_id = $2)",
+        )
+        .bind(parent_id)
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+        if !same_video {
+            return Err(AppError::Validation(
+                "parent_id must be a comment on this video".to_string(),
+            ));
+        }
+    }
+
+    let row = sqlx::query_as::<_, CommentRow>(&format!(
+        "WITH c AS (
+             INSERT INTO comments (video_id, parent_id, author_id, body, timestamp_seconds)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *
+         )
+         SELECT {}
+         FROM c
+         JOIN users u ON u.id = c.author_id",
+        COMMENT_COLUMNS
+    ))
+    .bind(id)
+    .bind(request.parent_id)
+    .bind(user.user_id)
+    .bind(body)
+    .bind(request.timestamp)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(row.into())))
+}
+
+// Deleting a comment also deletes its replies
+async fn delete_comment(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let access = require_video_member(&pool, id, user.user_id).await?;
+
+    // A comment on another video is reported as missing, not forbidden
+    let author_id = sqlx::query_scalar::<_, Uuid>("SELECT author_id FROM comments WHERE id = $1 AND video_id = $2")
+        .bind(comment_id)
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+    if author_id != user.user_id && !access.is_owner && access.role < Role::Admin {
+        return Err(AppError::Forbidden);
+    }
+
+    sqlx::query("DELETE FROM comments WHERE id = $1")
+        .bind(comment_id)
+        .execute(&pool)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/videos/:id/comments", get(list_comments))
+        .route("/videos/:id/comments", post(create_comment))
+        .route("/videos/:id/comments/:comment_id", delete(delete_comment))
+}
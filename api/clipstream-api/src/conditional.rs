@@ -0,0 +1,186 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+
+use crate::error::AppError;
+
+/// Weak ETag for a row, derived from its `updated_at`. Weak because the body
+/// may also carry joined data (uploader name, counts) that can change
+/// without touching the row.
+pub fn etag(updated_at: OffsetDateTime) -> String {
+    format!("W/\"{:x}\"", updated_at.unix_timestamp_nanos())
+}
+
+/// Whether an `If-None-Match` header value matches the current ETag. Uses
+/// weak comparison, as RFC 9110 requires for `If-None-Match`.
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+// `If-None-Match` wins when both are sent; `If-Modified-Since` only has
+// second precision, so compare against the truncated timestamp.
+fn not_modified(headers: &HeaderMap, etag: &str, updated_at: OffsetDateTime) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str().is_ok_and(|value| etag_matches(value, etag));
+    }
+
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| httpdate::parse_http_date(value).ok())
+        .is_some_and(|since| {
+            OffsetDateTime::from(since).unix_timestamp() >= updated_at.unix_timestamp()
+        })
+}
+
+/// Answer a read with `ETag`/`Last-Modified` taken from `updated_at`, or a
+/// bare `304 Not Modified` when the client's copy is still current.
+pub fn respond(headers: &HeaderMap, updated_at: OffsetDateTime, body: impl IntoResponse) -> Response {
+    let etag = etag(updated_at);
+    let last_modified = httpdate::fmt_http_date(SystemTime::from(updated_at));
+
+    let mut response = if not_modified(headers, &etag, updated_at) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        body.into_response()
+    };
+
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&last_modified) {
+        response_headers.insert(header::LAST_MODIFIED, value);
+    }
+    // Revalidate every time: membership can be revoked between fetches
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+    response
+}
+
+/// Answer with a JSON body and a strong ETag hashed from it, for responses
+/// assembled from many rows with no single `updated_at`. Clients may reuse
+/// it for `max_age` without asking; after that `If-None-Match` gets a `304`.
+pub fn respond_json<T: Serialize>(headers: &HeaderMap, max_age: Duration, body: &T) -> Result<Response, AppError> {
+    let body = serde_json::to_vec(body).map_err(|err| {
+        tracing::error!(error = %err, "Failed to serialize response");
+        AppError::Internal
+    })?;
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&body)[..16]));
+    let cache_control = format!("private, max-age={}", max_age.as_secs());
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, &etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control)]).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// What an update's `If-Match` header pins it to. Only versions are
+/// accepted: `If-Match` uses strong comparison (RFC 9110 §13.1.1), and the
+/// ETags from reads are weak, so they could never match.
+pub enum Precondition {
+    /// `*`: any current version.
+    Any,
+    /// A version number, as returned in the resource's `version` field.
+    Version(i32),
+}
+
+impl Precondition {
+    /// Parse `If-Match`; updates without one are rejected with `428`, and
+    /// weak ETags with `412`, since they never match.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        let value = headers
+            .get(header::IF_MATCH)
+            .ok_or(AppError::PreconditionRequired)?
+            .to_str()
+            .map_err(|_| AppError::BadRequest("Invalid If-Match header".to_string()))?
+            .trim();
+
+        if value == "*" {
+            return Ok(Precondition::Any);
+        }
+        if value.starts_with("W/") {
+            return Err(AppError::PreconditionFailed(
+                "Weak ETags never match If-Match; send the resource's version".to_string(),
+            ));
+        }
+        // Versions may be sent bare or quoted, as a strong ETag
+        value
+            .trim_matches('"')
+            .parse::<i32>()
+            .map(Precondition::Version)
+            .map_err(|_| AppError::BadRequest("If-Match must be a version number".to_string()))
+    }
+
+    /// Whether the resource, currently at `version`, still matches; a
+    /// mismatch means someone else edited it first.
+    pub fn check(&self, version: i32) -> Result<(), AppError> {
+        let matches = match self {
+            Precondition::Any => true,
+            Precondition::Version(expected) => *expected == version,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(AppError::PreconditionFailed(format!(
+                "Resource was modified; current version is {}",
+                version
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_match(value: &str) -> Result<Precondition, AppError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        Precondition::from_headers(&headers)
+    }
+
+    #[test]
+    fn accepts_bare_and_quoted_versions() {
+        for value in ["3", "\"3\""] {
+            let precondition = if_match(value).unwrap();
+            assert!(precondition.check(3).is_ok());
+            assert!(matches!(precondition.check(4), Err(AppError::PreconditionFailed(_))));
+        }
+        assert!(if_match("*").unwrap().check(7).is_ok());
+    }
+
+    #[test]
+    fn weak_etags_never_match() {
+        let weak = etag(OffsetDateTime::now_utc());
+        assert!(matches!(if_match(&weak), Err(AppError::PreconditionFailed(_))));
+        assert!(matches!(if_match("W/\"3\""), Err(AppError::PreconditionFailed(_))));
+    }
+
+    #[test]
+    fn rejects_other_values() {
+        assert!(matches!(if_match("\"abc\""), Err(AppError::BadRequest(_))));
+        assert!(matches!(Precondition::from_headers(&HeaderMap::new()), Err(AppError::PreconditionRequired)));
+    }
+}
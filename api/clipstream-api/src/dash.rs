@@ -0,0 +1,85 @@
+use std::fmt::Write;
+
+/// Length of each CMAF segment the transcoding stage writes.
+pub(crate) const SEGMENT_SECONDS: u32 = 4;
+
+/// One rung of the rendition ladder, with presigned storage URLs for its
+/// initialization segment and each media segment in order.
+pub(crate) struct Representation {
+    pub(crate) name: String,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) bitrate_kbps: i32,
+    pub(crate) codecs: String,
+    pub(crate) initialization_url: String,
+    pub(crate) segment_urls: Vec<String>,
+}
+
+/// How many [`SEGMENT_SECONDS`] segments a rendition of `duration_seconds` has.
+pub(crate) fn segment_count(duration_seconds: i32) -> u32 {
+    (duration_seconds.max(0) as u32).div_ceil(SEGMENT_SECONDS)
+}
+
+/// RFC 6381 codecs for a rendition the transcoding stage didn't record them
+/// for: H.264 at the profile and level its ladder uses for that height, with
+/// AAC-LC audio.
+pub(crate) fn default_codecs(height: i32) -> &'static str {
+    match height {
+        ..=480 => "avc1.4d401e,mp4a.40.2",
+        481..=720 => "avc1.4d401f,mp4a.40.2",
+        721..=1080 => "avc1.640028,mp4a.40.2",
+        _ => "avc1.640033,mp4a.40.2",
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A static MPEG-DASH manifest for the same fMP4 segments the HLS playlists
+/// use. Every segment is listed with its own URL, since presigned URLs can't
+/// be built from a template.
+pub(crate) fn manifest(duration_seconds: i32, representations: &[Representation]) -> String {
+    let mut mpd = String::new();
+    let _ = writeln!(mpd, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        mpd,
+        r#"<MPD xmlns="urn:mpeg:dash:schema:mpd:2011" profiles="urn:mpeg:dash:profile:full:2011" type="static" mediaPresentationDuration="PT{}S" minBufferTime="PT{}S">"#,
+        duration_seconds, SEGMENT_SECONDS
+    );
+    let _ = writeln!(mpd, r#"  <Period id="0" start="PT0S">"#);
+    let _ = writeln!(
+        mpd,
+        r#"    <AdaptationSet mimeType="video/mp4" segmentAlignment="true" startWithSAP="1">"#
+    );
+    for representation in representations {
+        let _ = writeln!(
+            mpd,
+            r#"      <Representation id="{}" codecs="{}" bandwidth="{}" width="{}" height="{}">"#,
+            escape(&representation.name),
+            escape(&representation.codecs),
+            i64::from(representation.bitrate_kbps) * 1000,
+            representation.width,
+            representation.height
+        );
+        let _ = writeln!(mpd, r#"        <SegmentList timescale="1" duration="{}">"#, SEGMENT_SECONDS);
+        let _ = writeln!(
+            mpd,
+            r#"          <Initialization sourceURL="{}"/>"#,
+            escape(&representation.initialization_url)
+        );
+        for url in &representation.segment_urls {
+            let _ = writeln!(mpd, r#"          <SegmentURL media="{}"/>"#, escape(url));
+        }
+        let _ = writeln!(mpd, "        </SegmentList>");
+        let _ = writeln!(mpd, "      </Representation>");
+    }
+    let _ = writeln!(mpd, "    </AdaptationSet>");
+    let _ = writeln!(mpd, "  </Period>");
+    let _ = writeln!(mpd, "</MPD>");
+    mpd
+}
@@ -0,0 +1,103 @@
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Error type returned by every handler. Each variant maps to a status code
+/// and a `{ "error": "..." }` JSON body.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound,
+    Unauthorized,
+    Forbidden,
+    Conflict(String),
+    Gone(String),
+    PayloadTooLarge(String),
+    /// `If-Match` didn't match the current version.
+    PreconditionFailed(String),
+    /// An update that needs `If-Match` was sent without it.
+    PreconditionRequired,
+    Validation(String),
+    /// Rate limited; carries the seconds until the caller may retry.
+    TooManyRequests(u64),
+    /// Maintenance mode; carries the seconds until the caller may retry.
+    ServiceUnavailable(u64),
+    Database(sqlx::Error),
+    Internal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+impl AppError {
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Gone(_) => StatusCode::GONE,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Database(_) | AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::BadRequest(message) => write!(f, "{}", message),
+            AppError::NotFound => write!(f, "Not found"),
+            AppError::Unauthorized => write!(f, "Authentication required"),
+            AppError::Forbidden => write!(f, "Access denied"),
+            AppError::TooManyRequests(_) => write!(f, "Too many requests"),
+            AppError::ServiceUnavailable(_) => write!(f, "Down for maintenance; please try again later"),
+            AppError::PreconditionRequired => write!(f, "If-Match header is required"),
+            AppError::Conflict(message)
+            | AppError::Gone(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::PreconditionFailed(message)
+            | AppError::Validation(message) => write!(f, "{}", message),
+            // Never leak database details to clients
+            AppError::Database(_) | AppError::Internal => write!(f, "Internal server error"),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Database(err) = &self {
+            tracing::error!(error = %err, "Database error");
+        }
+
+        let body = ErrorBody {
+            error: self.to_string(),
+        };
+        let mut response = (self.status(), Json(body)).into_response();
+        if let AppError::TooManyRequests(retry_after) | AppError::ServiceUnavailable(retry_after) = self {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after.max(1)));
+        }
+        response
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => AppError::NotFound,
+            err => AppError::Database(err),
+        }
+    }
+}
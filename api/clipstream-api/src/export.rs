@@ -0,0 +1,240 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::AppError,
+    processing,
+    query::{Validate, ValidQuery},
+    streams::{self, Role},
+};
+
+// Rows fetched per query while streaming, which bounds memory use per export
+const EXPORT_BATCH: i64 = 500;
+const CSV_HEADER: &str = "id,title,duration_seconds,size_bytes,processing_index,status,uploader_id,uploader_name,created_at\n";
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(format: Option<&str>) -> Option<ExportFormat> {
+        match format {
+            None | Some("csv") => Some(ExportFormat::Csv),
+            Some("json") => Some(ExportFormat::Json),
+            Some(_) => None,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    /// `csv` (default) or `json`.
+    format: Option<String>,
+}
+
+impl Validate for ExportQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if ExportFormat::parse(self.format.as_deref()).is_none() {
+            return Err(AppError::Validation("format must be csv or json".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct ExportedVideo {
+    id: Uuid,
+    title: String,
+    duration_seconds: Option<i32>,
+    size_bytes: Option<i64>,
+    processing_index: i32,
+    #[sqlx(skip)]
+    status: &'static str,
+    uploader_id: Uuid,
+    uploader_name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+// Quoted when needed, and with a leading `'` on anything a spreadsheet
+// would otherwise evaluate as a formula
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_row(video: &ExportedVideo) -> String {
+    let optional = |value: Option<i64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let created_at = video
+        .created_at
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default();
+    format!(
+        "{},{},{},{},{},{},{},{},{}\n",
+        video.id,
+        csv_field(&video.title),
+        optional(video.duration_seconds.map(i64::from)),
+        optional(video.size_bytes),
+        video.processing_index,
+        video.status,
+        video.uploader_id,
+        csv_field(&video.uploader_name),
+        created_at
+    )
+}
+
+async fn fetch_batch(
+    pool: &PgPool,
+    stream_id: Uuid,
+    after: Option<(OffsetDateTime, Uuid)>,
+) -> Result<Vec<ExportedVideo>, sqlx::Error> {
+    let mut videos = sqlx::query_as::<_, ExportedVideo>(
+        "SELECT v.id, v.title, v.duration_seconds, v.size_bytes, v.processing_index,
+                v.uploader_id, u.display_name AS uploader_name, v.created_at
+         FROM videos v
+         JOIN users u ON u.id = v.uploader_id
+         WHERE v.stream_id = $1
+           AND v.deleted_at IS NULL
+           AND ($2::timestamptz IS NULL OR (v.created_at, v.id) > ($2, $3))
+         ORDER BY v.created_at, v.id
+         LIMIT $4",
+    )
+    .bind(stream_id)
+    .bind(after.map(|(created_at, _)| created_at))
+    .bind(after.map(|(_, id)| id))
+    .bind(EXPORT_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    for video in &mut videos {
+        video.status = processing::status_name(video.processing_index);
+    }
+    Ok(videos)
+}
+
+struct ExportState {
+    pool: PgPool,
+    stream_id: Uuid,
+    format: ExportFormat,
+    /// Keyset position of the last row sent; `None` before the first batch.
+    after: Option<(OffsetDateTime, Uuid)>,
+    done: bool,
+}
+
+// One chunk per batch: the header (or `[`) goes with the first, the closing
+// `]` with the last
+async fn next_chunk(mut state: ExportState) -> Option<(Result<String, sqlx::Error>, ExportState)> {
+    if state.done {
+        return None;
+    }
+    let first = state.after.is_none();
+    let videos = match fetch_batch(&state.pool, state.stream_id, state.after).await {
+        Ok(videos) => videos,
+        Err(err) => {
+            // The status line has already gone out, so all that's left is to cut the body short
+            tracing::error!(stream_id = %state.stream_id, error = %err, "Stream export failed");
+            state.done = true;
+            return Some((Err(err), state));
+        }
+    };
+
+    let mut chunk = String::new();
+    match state.format {
+        ExportFormat::Csv if first => chunk.push_str(CSV_HEADER),
+        ExportFormat::Json if first => chunk.push('['),
+        _ => {}
+    }
+    for (index, video) in videos.iter().enumerate() {
+        match state.format {
+            ExportFormat::Csv => chunk.push_str(&csv_row(video)),
+            ExportFormat::Json => {
+                if !(first && index == 0) {
+                    chunk.push(',');
+                }
+                chunk.push_str(&serde_json::to_string(video).unwrap_or_default());
+            }
+        }
+    }
+
+    state.done = (videos.len() as i64) < EXPORT_BATCH;
+    if state.done && matches!(state.format, ExportFormat::Json) {
+        chunk.push(']');
+    }
+    // An empty stream still needs a position so the header isn't repeated
+    state.after = match videos.last() {
+        Some(video) => Some((video.created_at, video.id)),
+        None => state.after.or(Some((OffsetDateTime::UNIX_EPOCH, Uuid::nil()))),
+    };
+    Some((Ok(chunk), state))
+}
+
+async fn export_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<ExportQuery>,
+) -> Result<Response, AppError> {
+    // Checked by `validate`
+    let format = ExportFormat::parse(query.format.as_deref()).unwrap_or(ExportFormat::Csv);
+
+    streams::require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    let state = ExportState {
+        pool,
+        stream_id: id,
+        format,
+        after: None,
+        done: false,
+    };
+    let body = Body::from_stream(futures::stream::unfold(state, next_chunk));
+    let filename = format!("stream-{}-videos.{}", id, format.extension());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new().route("/streams/:id/export", get(export_stream))
+}
@@ -1,42 +1,734 @@
 use axum::{
-    extract::Path,
-    response::Json,
-    routing::{get, post},
+    body::Body,
+    extract::{Path, State},
+    http::{header, Extensions, HeaderMap, StatusCode, Uri},
+    middleware,
+    response::{IntoResponse, Json, Response},
+    routing::get,
     Router,
 };
-use serde::Serialize;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use sqlx::PgPool;
+use std::{collections::HashMap, ops::Range};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    conditional, config,
+    dash::{self, Representation},
+    error::AppError,
+    hls,
+    processing,
+    query::{Validate, ValidQuery},
+    rate_limit::{self, Group},
+    signing::{self, Purpose},
+    storage,
+    streams::{self, Role},
+    videos::{self, ThumbnailSize},
+};
+
+const THUMBNAIL_CACHE_CONTROL: &str = "private, max-age=300";
+// Sprites are only rewritten on reprocessing, and the ETag covers that
+const STORYBOARD_CACHE_CONTROL: &str = "private, max-age=86400";
+
+fn storage_error(err: object_store::Error) -> AppError {
+    match err {
+        object_store::Error::NotFound { .. } => AppError::NotFound,
+        err => {
+            tracing::error!(error = %err, "Storage error");
+            AppError::Internal
+        }
+    }
+}
+
+enum ByteRange {
+    Full,
+    Partial(Range<u64>),
+    Unsatisfiable,
+}
+
+// Resolve a `Range` header against an object of `size` bytes. Only single
+// ranges are supported; anything else is ignored and the whole object served.
+fn parse_range(value: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = value.and_then(|value| value.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+
+    match (start.trim(), end.trim()) {
+        // Suffix range: the last N bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if size == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial(size.saturating_sub(suffix)..size),
+            Err(_) => ByteRange::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            let end = match end {
+                "" => size,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => (end + 1).min(size),
+                    _ => return ByteRange::Full,
+                },
+            };
+            if start >= size {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial(start..end)
+            }
+        }
+    }
+}
+
+fn content_type_for(key: &str) -> &'static str {
+    let extension = key.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    videos::ALLOWED_UPLOAD_TYPES
+        .iter()
+        .find(|(_, ext)| *ext == extension)
+        .map(|(content_type, _)| *content_type)
+        .unwrap_or("application/octet-stream")
+}
+
+#[derive(Deserialize)]
+struct ThumbnailQuery {
+    share: Option<String>,
+    /// `small`, `medium` or `large`; the full-size thumbnail if omitted.
+    size: Option<String>,
+    /// Pick the smallest size at least this many pixels wide instead.
+    w: Option<u32>,
+}
+
+impl ThumbnailQuery {
+    // Checked by `validate`
+    fn thumbnail_size(&self) -> Option<ThumbnailSize> {
+        match (&self.size, self.w) {
+            (Some(size), _) => ThumbnailSize::parse(size),
+            (None, Some(width)) => Some(ThumbnailSize::for_width(width)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Validate for ThumbnailQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.size.is_some() && self.w.is_some() {
+            return Err(AppError::Validation("Pass either size or w, not both".to_string()));
+        }
+        if self.size.as_deref().is_some_and(|size| ThumbnailSize::parse(size).is_none()) {
+            return Err(AppError::Validation("size must be small, medium or large".to_string()));
+        }
+        if self.w == Some(0) {
+            return Err(AppError::Validation("w must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+// Served in place of a thumbnail that hasn't been generated yet
+fn placeholder_thumbnail(processing_index: i32) -> Response {
+    let label = match processing_index {
+        processing::DUPLICATE => "Duplicate",
+        processing::REJECTED => "Rejected",
+        _ => "Processing",
+    };
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="640" height="360" viewBox="0 0 640 360"><rect width="640" height="360" fill="#2a2a2e"/><text x="320" y="188" fill="#9a9aa2" font-family="sans-serif" font-size="32" text-anchor="middle">{}</text></svg>"##,
+        label
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        svg,
+    )
+        .into_response()
+}
+
+fn thumbnail_content_type(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+async fn get_thumbnail(
+    State(pool): State<PgPool>,
+    user: Option<AuthUser>,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<ThumbnailQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let size = query.thumbnail_size();
+
+    let (stream_id, processing_index, thumbnail_path) = sqlx::query_as::<_, (Uuid, i32, Option<String>)>(
+        "SELECT stream_id, processing_index, thumbnail_path FROM videos WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    // Anyone holding a live share link for this video may see its thumbnail
+    if let Some(code) = query.share {
+        let shared = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                 SELECT 1 FROM shares
+                 WHERE code = $1 AND video_id = $2 AND revoked_at IS NULL
+                   AND (expires_at IS NULL OR expires_at > NOW())
+             )",
+        )
+        .bind(code)
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+        if !shared {
+            return Err(AppError::Forbidden);
+        }
+    } else {
+        let user = user.ok_or(AppError::Unauthorized)?;
+        streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+    }
+
+    let Some(thumbnail_path) = thumbnail_path else {
+        return Ok(placeholder_thumbnail(processing_index));
+    };
+    // A size that processing didn't generate is missing from storage: `404`
+    let key = match size {
+        Some(size) => size.key(&thumbnail_path),
+        None => thumbnail_path,
+    };
+
+    let meta = storage::head(&key).await.map_err(storage_error)?;
+    let etag = meta.e_tag.unwrap_or_else(|| {
+        let variant = size.map_or("full", ThumbnailSize::as_str);
+        format!("\"{}-{}\"", variant, meta.last_modified.timestamp())
+    });
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| conditional::etag_matches(value, &etag))
+    {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, THUMBNAIL_CACHE_CONTROL.to_string())],
+        )
+            .into_response());
+    }
+
+    let body = Body::from_stream(storage::get_stream(&key, None).await.map_err(storage_error)?);
+    Ok((
+        [
+            (header::CONTENT_TYPE, thumbnail_content_type(&key).to_string()),
+            (header::CONTENT_LENGTH, meta.size.to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, THUMBNAIL_CACHE_CONTROL.to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+async fn get_storyboard_sprite(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let (stream_id, has_storyboard) = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT v.stream_id, EXISTS (SELECT 1 FROM storyboards WHERE video_id = v.id)
+         FROM videos v
+         WHERE v.id = $1 AND v.deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    if !has_storyboard {
+        return Err(AppError::Conflict("Storyboard not ready".to_string()));
+    }
+
+    let key = videos::storyboard_key(id);
+    let meta = storage::head(&key).await.map_err(storage_error)?;
+    let etag = meta.e_tag.unwrap_or_else(|| format!("\"{}\"", meta.last_modified.timestamp()));
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| conditional::etag_matches(value, &etag))
+    {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, STORYBOARD_CACHE_CONTROL.to_string())],
+        )
+            .into_response());
+    }
+
+    let body = Body::from_stream(storage::get_stream(&key, None).await.map_err(storage_error)?);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg".to_string()),
+            (header::CONTENT_LENGTH, meta.size.to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, STORYBOARD_CACHE_CONTROL.to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+// Manifests and playlists accept either an app token or a signed URL
+async fn require_playback(pool: &PgPool, user: Option<AuthUser>, uri: &Uri, stream_id: Uuid) -> Result<(), AppError> {
+    match user {
+        Some(user) => {
+            streams::require_stream_role(pool, stream_id, user.user_id, Role::Viewer).await?;
+        }
+        None => signing::verify(uri)?,
+    }
+    Ok(())
+}
+
+// Players fetch this without an app token, through the signed `dash_url`
+async fn get_dash_manifest(
+    State(pool): State<PgPool>,
+    user: Option<AuthUser>,
+    Path(id): Path<Uuid>,
+    uri: Uri,
+) -> Result<Response, AppError> {
+    let (stream_id, processing_index, duration_seconds) = sqlx::query_as::<_, (Uuid, i32, Option<i32>)>(
+        "SELECT stream_id, processing_index, duration_seconds FROM videos WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    require_playback(&pool, user, &uri, stream_id).await?;
+
+    if processing_index != processing::READY {
+        return Err(AppError::Conflict("Video is still processing".to_string()));
+    }
+
+    let renditions = sqlx::query_as::<_, (String, i32, i32, i32, String, Option<String>)>(
+        "SELECT name, width, height, bitrate_kbps, playlist_path, codecs
+         FROM video_renditions
+         WHERE video_id = $1
+         ORDER BY height, bitrate_kbps",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+    // A ready video without renditions or a duration means transcoding output is missing
+    let Some(duration_seconds) = duration_seconds.filter(|_| !renditions.is_empty()) else {
+        return Err(AppError::Internal);
+    };
+
+    // Segments sit next to each HLS playlist. Each one gets its own presigned
+    // URL, since a signed directory URL can't be extended with a file name.
+    let mut representations = Vec::with_capacity(renditions.len());
+    for (name, width, height, bitrate_kbps, playlist_path, codecs) in renditions {
+        let Some((directory, _)) = playlist_path.rsplit_once('/') else {
+            return Err(AppError::Internal);
+        };
+        let initialization_url = signing::storage_get(&format!("{}/init.mp4", directory), Purpose::Stream).await?.url;
+        let segment_urls = futures::future::try_join_all((0..dash::segment_count(duration_seconds)).map(|number| {
+            let key = format!("{}/segment_{}.m4s", directory, number);
+            async move { signing::storage_get(&key, Purpose::Stream).await.map(|signed| signed.url) }
+        }))
+        .await?;
+        representations.push(Representation {
+            codecs: codecs.unwrap_or_else(|| dash::default_codecs(height).to_string()),
+            name,
+            width,
+            height,
+            bitrate_kbps,
+            initialization_url,
+            segment_urls,
+        });
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/dash+xml"),
+            (header::CACHE_CONTROL, "private, no-cache"),
+        ],
+        dash::manifest(duration_seconds, &representations),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+struct PlaylistQuery {
+    /// Set in URLs from `GET /share/{code}`, covered by their signature, so
+    /// the URLs in the playlist get the share link lifetime.
+    #[serde(default)]
+    share: bool,
+}
+
+impl Validate for PlaylistQuery {}
+
+impl PlaylistQuery {
+    fn purpose(&self) -> Purpose {
+        if self.share { Purpose::Share } else { Purpose::Stream }
+    }
+}
+
+fn playlist_path(id: Uuid, file: &str, purpose: Purpose) -> String {
+    let share = if matches!(purpose, Purpose::Share) { "?share=true" } else { "" };
+    format!("/files/videos/{}/{}{}", id, file, share)
+}
+
+/// Signed URL of the API's copy of a video's HLS master playlist. Players
+/// can't fetch the stored playlists directly: a presigned URL covers only the
+/// one object, so the variant playlists and segments they reference would
+/// be unsigned.
+pub(crate) fn hls_master_url(id: Uuid, purpose: Purpose) -> signing::SignedUrl {
+    signing::sign_path(&playlist_path(id, "master.m3u8", purpose), purpose)
+}
+
+async fn get_playlist(key: &str) -> Result<String, AppError> {
+    let bytes = storage::get(key).await.map_err(storage_error)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| AppError::Internal)
+}
+
+fn playlist_response(playlist: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, hls::CONTENT_TYPE),
+            (header::CACHE_CONTROL, "private, no-cache"),
+        ],
+        playlist,
+    )
+        .into_response()
+}
+
+// The stored master playlist with each variant playlist pointed at
+// `get_hls_rendition`, signed the same way as this request
+async fn get_hls_master(
+    State(pool): State<PgPool>,
+    user: Option<AuthUser>,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<PlaylistQuery>,
+    uri: Uri,
+) -> Result<Response, AppError> {
+    let (stream_id, processing_index, hls_master_path) =
+        videos::fetch_video::<(Uuid, i32, Option<String>)>(&pool, id, "stream_id, processing_index, hls_master_path")
+            .await?;
+
+    require_playback(&pool, user, &uri, stream_id).await?;
+
+    let hls_master_path = match (processing_index, hls_master_path) {
+        (processing::READY, Some(path)) => path,
+        (processing::READY, None) => return Err(AppError::Internal),
+        _ => return Err(AppError::Conflict("Video is still processing".to_string())),
+    };
+
+    let renditions = sqlx::query_as::<_, (String, String)>(
+        "SELECT name, playlist_path FROM video_renditions WHERE video_id = $1",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+
+    let purpose = query.purpose();
+    let master = get_playlist(&hls_master_path).await?;
+    let mut signed = HashMap::new();
+    for uri in hls::uris(&master) {
+        let Some(key) = hls::resolve(&hls_master_path, uri) else {
+            continue;
+        };
+        // Anything that isn't a rendition playlist (an I-frame playlist, say) is presigned as is
+        let url = match renditions.iter().find(|(_, playlist_path)| *playlist_path == key) {
+            Some((name, _)) => {
+                let path = playlist_path(id, &format!("renditions/{}/playlist.m3u8", name), purpose);
+                signing::sign_path(&path, purpose).url
+            }
+            None => signing::storage_get(&key, purpose).await?.url,
+        };
+        signed.insert(uri, url);
+    }
+
+    let playlist = hls::rewrite_uris(&master, |uri| signed.get(uri).cloned().unwrap_or_else(|| uri.to_string()));
+    Ok(playlist_response(playlist))
+}
+
+// A rendition's stored media playlist with its init and media segments
+// presigned one by one
+async fn get_hls_rendition(
+    State(pool): State<PgPool>,
+    user: Option<AuthUser>,
+    Path((id, name)): Path<(Uuid, String)>,
+    ValidQuery(query): ValidQuery<PlaylistQuery>,
+    uri: Uri,
+) -> Result<Response, AppError> {
+    let (stream_id, processing_index) =
+        videos::fetch_video::<(Uuid, i32)>(&pool, id, "stream_id, processing_index").await?;
+
+    require_playback(&pool, user, &uri, stream_id).await?;
+
+    if processing_index != processing::READY {
+        return Err(AppError::Conflict("Video is still processing".to_string()));
+    }
+
+    let playlist_key = sqlx::query_scalar::<_, String>(
+        "SELECT playlist_path FROM video_renditions WHERE video_id = $1 AND name = $2",
+    )
+    .bind(id)
+    .bind(&name)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let purpose = query.purpose();
+    let media = get_playlist(&playlist_key).await?;
+    let mut keys: Vec<(&str, String)> = hls::uris(&media)
+        .into_iter()
+        .filter_map(|uri| hls::resolve(&playlist_key, uri).map(|key| (uri, key)))
+        .collect();
+    keys.dedup();
+    let urls = futures::future::try_join_all(
+        keys.iter()
+            .map(|(_, key)| async move { signing::storage_get(key, purpose).await.map(|signed| signed.url) }),
+    )
+    .await?;
+    let signed: HashMap<&str, String> = keys.iter().map(|(uri, _)| *uri).zip(urls).collect();
+
+    let playlist = hls::rewrite_uris(&media, |uri| signed.get(uri).cloned().unwrap_or_else(|| uri.to_string()));
+    Ok(playlist_response(playlist))
+}
+
+// Failing to record a view shouldn't fail playback
+async fn record_view(pool: &PgPool, user_id: Uuid, video_id: Uuid) {
+    let recorded = sqlx::query(
+        "INSERT INTO video_views (user_id, video_id) VALUES ($1, $2)
+         ON CONFLICT (user_id, video_id) DO UPDATE SET viewed_at = NOW()",
+    )
+    .bind(user_id)
+    .bind(video_id)
+    .execute(pool)
+    .await;
+
+    if let Err(err) = recorded {
+        tracing::warn!(video_id = %video_id, error = %err, "Failed to record video view");
+    }
+}
+
+#[derive(Deserialize)]
+struct StreamVideoQuery {
+    /// Set in URLs from `GET /videos/{id}/stream`, covered by their signature.
+    viewer: Option<Uuid>,
+}
+
+impl Validate for StreamVideoQuery {}
+
+// Members authenticate with an app token; `<video>` elements, which can't
+// send one, use the signed `file_url` instead
+async fn stream_video(
+    State(pool): State<PgPool>,
+    user: Option<AuthUser>,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<StreamVideoQuery>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let (stream_id, storage_path) = sqlx::query_as::<_, (Uuid, Option<String>)>(
+        "SELECT stream_id, storage_path FROM videos WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    // Membership was checked when the URL was signed
+    let viewer = match user {
+        Some(user) => {
+            streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+            Some(user.user_id)
+        }
+        None => {
+            signing::verify(&uri)?;
+            query.viewer
+        }
+    };
+
+    // Nothing to serve until the original has been uploaded
+    let key = storage_path.ok_or(AppError::NotFound)?;
+    let size = storage::head(&key).await.map_err(storage_error)?.size as u64;
+
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let (status, range) = match parse_range(range, size) {
+        ByteRange::Full => (StatusCode::OK, 0..size),
+        ByteRange::Partial(range) => (StatusCode::PARTIAL_CONTENT, range),
+        ByteRange::Unsatisfiable => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [
+                    (header::ACCEPT_RANGES, "bytes".to_string()),
+                    (header::CONTENT_RANGE, format!("bytes */{}", size)),
+                ],
+            )
+                .into_response());
+        }
+    };
+
+    // Players fetch many ranges per playback; count the one from the start
+    if range.start == 0
+        && let Some(viewer) = viewer
+    {
+        record_view(&pool, viewer, id).await;
+    }
+
+    let body = if range.is_empty() {
+        Body::empty()
+    } else {
+        let requested = (status == StatusCode::PARTIAL_CONTENT).then(|| range.clone());
+        Body::from_stream(storage::get_stream(&key, requested).await.map_err(storage_error)?)
+    };
+
+    let mut response = (
+        status,
+        [
+            (header::CONTENT_TYPE, content_type_for(&key).to_string()),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (header::CONTENT_LENGTH, (range.end - range.start).to_string()),
+        ],
+        body,
+    )
+        .into_response();
+
+    if status == StatusCode::PARTIAL_CONTENT {
+        let content_range = format!("bytes {}-{}/{}", range.start, range.end - 1, size);
+        response
+            .headers_mut()
+            .insert(header::CONTENT_RANGE, content_range.parse().map_err(|_| AppError::Internal)?);
+    }
+
+    Ok(response)
+}
+
+#[derive(sqlx::FromRow)]
+struct SharedVideoRow {
+    share_id: Uuid,
+    expires_at: Option<OffsetDateTime>,
+    max_views: Option<i32>,
+    views_count: i32,
+    revoked_at: Option<OffsetDateTime>,
+    video_id: Uuid,
+    title: String,
+    duration_seconds: Option<i32>,
+    processing_index: i32,
+    hls_master_path: Option<String>,
+}
 
 #[derive(Serialize)]
-struct FilesResponse {
-    message: String,
-    purpose: String,
+struct SharedVideo {
+    id: Uuid,
+    title: String,
+    duration_seconds: Option<i32>,
 }
 
-async fn get_thumbnail(Path(id): Path<String>) -> Json<FilesResponse> {
-    Json(FilesResponse {
-        message: format!("Get thumbnail for video {} endpoint", id),
-        purpose: "Serve video thumbnail image file with processing overlays for status indication".to_string(),
-    })
+#[derive(Serialize)]
+struct SharedVideoStream {
+    video: SharedVideo,
+    hls_master_url: String,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
 }
 
-async fn stream_video(Path(id): Path<String>) -> Json<FilesResponse> {
-    Json(FilesResponse {
-        message: format!("Stream video {} file endpoint", id),
-        purpose: "Serve video file stream for direct playback or download".to_string(),
-    })
+// Keyed with the server secret and scoped to the share, so stored hashes
+// can't be reversed by hashing every IPv4 address or linked across shares
+fn viewer_ip_hash(share_id: Uuid, ip: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config::get().jwt_secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(share_id.as_bytes());
+    mac.update(ip.as_bytes());
+    hex::encode(&mac.finalize().into_bytes()[..16])
 }
 
-async fn access_shared_video(Path(code): Path<String>) -> Json<FilesResponse> {
-    Json(FilesResponse {
-        message: format!("Access shared video with code {} endpoint", code),
-        purpose: "Access video through shareable link with expiration validation".to_string(),
-    })
+async fn access_shared_video(
+    State(pool): State<PgPool>,
+    Path(code): Path<String>,
+    headers: HeaderMap,
+    extensions: Extensions,
+) -> Result<Json<SharedVideoStream>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    // Lock the share so concurrent views can't push it past max_views
+    let row = sqlx::query_as::<_, SharedVideoRow>(
+        "SELECT s.id AS share_id, s.expires_at, s.max_views, s.views_count, s.revoked_at,
+                v.id AS video_id, v.title, v.duration_seconds, v.processing_index, v.hls_master_path
+         FROM shares s
+         JOIN videos v ON v.id = s.video_id
+         WHERE s.code = $1 AND v.deleted_at IS NULL
+         FOR UPDATE OF s",
+    )
+    .bind(&code)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if row.revoked_at.is_some() {
+        return Err(AppError::Gone("Share link has been revoked".to_string()));
+    }
+    if row.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err(AppError::Gone("Share link has expired".to_string()));
+    }
+    match (row.processing_index, row.hls_master_path) {
+        (processing::READY, Some(_)) => {}
+        (processing::READY, None) => return Err(AppError::Internal),
+        _ => return Err(AppError::Conflict("Video is still processing".to_string())),
+    };
+    if row.max_views.is_some_and(|max_views| row.views_count >= max_views) {
+        return Err(AppError::Gone("Share link view limit reached".to_string()));
+    }
+
+    sqlx::query("UPDATE shares SET views_count = views_count + 1 WHERE id = $1")
+        .bind(row.share_id)
+        .execute(&mut *tx)
+        .await?;
+    let ip = rate_limit::client_ip(&headers, &extensions).unwrap_or_default();
+    sqlx::query("INSERT INTO share_views (share_id, ip_hash) VALUES ($1, $2)")
+        .bind(row.share_id)
+        .bind(viewer_ip_hash(row.share_id, &ip))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let hls_master = hls_master_url(row.video_id, Purpose::Share);
+
+    Ok(Json(SharedVideoStream {
+        video: SharedVideo {
+            id: row.video_id,
+            title: row.title,
+            duration_seconds: row.duration_seconds,
+        },
+        hls_master_url: hls_master.url,
+        expires_at: hls_master.expires_at,
+    }))
 }
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/files/videos/:id/thumbnail", get(get_thumbnail))
+        .route("/files/videos/:id/storyboard.jpg", get(get_storyboard_sprite))
         .route("/files/videos/:id/stream", get(stream_video))
-        .route("/share/:code", get(access_shared_video))
+        .route("/files/videos/:id/manifest.mpd", get(get_dash_manifest))
+        .route("/files/videos/:id/master.m3u8", get(get_hls_master))
+        .route("/files/videos/:id/renditions/:name/playlist.m3u8", get(get_hls_rendition))
+        .route(
+            "/share/:code",
+            get(access_shared_video).layer(middleware::from_fn_with_state(Group::Share, rate_limit::enforce)),
+        )
 }
@@ -0,0 +1,99 @@
+use std::ops::Range;
+
+pub(crate) const CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+
+// Where the URI sits in one playlist line: the whole line for a URI line,
+// or the quoted `URI="..."` attribute of tags such as `#EXT-X-MAP`
+fn uri_range(line: &str) -> Option<Range<usize>> {
+    let line = line.trim_end();
+    if line.is_empty() {
+        return None;
+    }
+    if !line.starts_with('#') {
+        return Some(0..line.len());
+    }
+    let start = line.find("URI=\"")? + "URI=\"".len();
+    let end = start + line[start..].find('"')?;
+    Some(start..end)
+}
+
+/// Every URI an HLS playlist references, in order, as written.
+pub(crate) fn uris(playlist: &str) -> Vec<&str> {
+    playlist
+        .lines()
+        .filter_map(|line| uri_range(line).map(|range| &line[range]))
+        .collect()
+}
+
+/// The playlist with each URI replaced by `rewrite(uri)`.
+pub(crate) fn rewrite_uris(playlist: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    let mut rewritten = String::with_capacity(playlist.len());
+    for line in playlist.lines() {
+        match uri_range(line) {
+            Some(range) => {
+                rewritten.push_str(&line[..range.start]);
+                rewritten.push_str(&rewrite(&line[range.clone()]));
+                rewritten.push_str(line[range.end..].trim_end());
+            }
+            None => rewritten.push_str(line.trim_end()),
+        }
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+/// Storage key of `uri` as referenced from the playlist at `playlist_key`.
+/// Absolute URLs aren't in the bucket and give `None`.
+pub(crate) fn resolve(playlist_key: &str, uri: &str) -> Option<String> {
+    if uri.contains("://") || uri.starts_with('/') {
+        return None;
+    }
+    Some(match playlist_key.rsplit_once('/') {
+        Some((directory, _)) => format!("{}/{}", directory, uri),
+        None => uri.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEDIA: &str = "#EXTM3U\r\n\
+        #EXT-X-VERSION:7\r\n\
+        #EXT-X-MAP:URI=\"init.mp4\"\r\n\
+        #EXTINF:4.000,\r\n\
+        segment_0.m4s\r\n\
+        #EXTINF:1.500,\r\n\
+        segment_1.m4s\r\n\
+        #EXT-X-ENDLIST\r\n";
+
+    #[test]
+    fn finds_uri_lines_and_attributes() {
+        assert_eq!(uris(MEDIA), ["init.mp4", "segment_0.m4s", "segment_1.m4s"]);
+    }
+
+    #[test]
+    fn rewrites_only_the_uris() {
+        let rewritten = rewrite_uris(MEDIA, |uri| format!("https://cdn.test/{}?sig=a&b", uri));
+        assert_eq!(
+            rewritten,
+            "#EXTM3U\n\
+             #EXT-X-VERSION:7\n\
+             #EXT-X-MAP:URI=\"https://cdn.test/init.mp4?sig=a&b\"\n\
+             #EXTINF:4.000,\n\
+             https://cdn.test/segment_0.m4s?sig=a&b\n\
+             #EXTINF:1.500,\n\
+             https://cdn.test/segment_1.m4s?sig=a&b\n\
+             #EXT-X-ENDLIST\n"
+        );
+    }
+
+    #[test]
+    fn resolves_relative_to_the_playlist() {
+        let master = "processed-videos/5f0c/master.m3u8";
+        assert_eq!(resolve(master, "720p/playlist.m3u8").as_deref(), Some("processed-videos/5f0c/720p/playlist.m3u8"));
+        assert_eq!(resolve("master.m3u8", "720p.m3u8").as_deref(), Some("720p.m3u8"));
+        assert_eq!(resolve(master, "https://cdn.test/720p.m3u8"), None);
+        assert_eq!(resolve(master, "/720p.m3u8"), None);
+    }
+}
@@ -0,0 +1,195 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{auth, config, error::AppError};
+
+const MAX_KEY_LEN: usize = 255;
+// Request bodies are small JSON documents; uploads go straight to storage
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+static IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+static REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+#[derive(sqlx::FromRow)]
+struct StoredResponse {
+    request_hash: String,
+    status_code: Option<i32>,
+    content_type: Option<String>,
+    response_body: Option<Vec<u8>>,
+}
+
+fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+fn request_hash(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b" ");
+    hasher.update(path);
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = stored
+        .status_code
+        .and_then(|code| StatusCode::from_u16(code as u16).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.response_body.unwrap_or_default()).into_response();
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored.content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(REPLAYED.clone(), HeaderValue::from_static("true"));
+    response
+}
+
+/// Honor an `Idempotency-Key` header on mutating requests from signed-in
+/// users. The first response for a key is stored and replayed for retries
+/// within `IDEMPOTENCY_KEY_TTL_SECS`; reusing the key with a different
+/// method, path or body is a `409`. Requests without the header pass through.
+pub async fn enforce(State(pool): State<PgPool>, request: Request, next: Next) -> Result<Response, AppError> {
+    let Some(key) = request.headers().get(&IDEMPOTENCY_KEY) else {
+        return Ok(next.run(request).await);
+    };
+    // Unauthenticated requests are rejected by the handler anyway
+    let Some(user_id) = auth::bearer_user_id(request.headers()) else {
+        return Ok(next.run(request).await);
+    };
+    if !is_mutating(request.method()) {
+        return Ok(next.run(request).await);
+    }
+
+    let key = key
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!("Idempotency-Key must be 1-{} visible ASCII characters", MAX_KEY_LEN))
+        })?
+        .to_string();
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::PayloadTooLarge("Request body is too large".to_string()))?;
+    let hash = request_hash(&parts.method, parts.uri.path(), &body);
+
+    if let Some(stored) = claim_key(&pool, user_id, &key, &hash).await? {
+        if stored.request_hash != hash {
+            return Err(AppError::Conflict(
+                "Idempotency-Key was already used for a different request".to_string(),
+            ));
+        }
+        if stored.status_code.is_none() {
+            return Err(AppError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            ));
+        }
+        return Ok(replay(stored));
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(_) => {
+            release_key(&pool, user_id, &key).await;
+            return Err(AppError::Internal);
+        }
+    };
+
+    // Server errors and rate limiting are transient, so let the client retry them
+    if parts.status.is_server_error() || parts.status == StatusCode::TOO_MANY_REQUESTS {
+        release_key(&pool, user_id, &key).await;
+    } else {
+        let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok());
+        let stored = sqlx::query(
+            "UPDATE idempotency_keys
+             SET status_code = $3, content_type = $4, response_body = $5
+             WHERE user_id = $1 AND key = $2",
+        )
+        .bind(user_id)
+        .bind(&key)
+        .bind(parts.status.as_u16() as i32)
+        .bind(content_type)
+        .bind(body.as_ref())
+        .execute(&pool)
+        .await;
+        if let Err(err) = stored {
+            tracing::error!(error = %err, "Failed to store idempotent response");
+            release_key(&pool, user_id, &key).await;
+        }
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+// Reserve the key for this request. Returns the existing record if the key
+// was already claimed and hasn't expired.
+async fn claim_key(pool: &PgPool, user_id: Uuid, key: &str, hash: &str) -> Result<Option<StoredResponse>, AppError> {
+    let ttl_secs = config::get().idempotency_key_ttl.as_secs_f64();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "DELETE FROM idempotency_keys
+         WHERE user_id = $1 AND created_at < NOW() - make_interval(secs => $2)",
+    )
+    .bind(user_id)
+    .bind(ttl_secs)
+    .execute(&mut *tx)
+    .await?;
+
+    let claimed = sqlx::query(
+        "INSERT INTO idempotency_keys (user_id, key, request_hash)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (user_id, key) DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(hash)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        == 1;
+
+    let existing = if claimed {
+        None
+    } else {
+        let stored = sqlx::query_as::<_, StoredResponse>(
+            "SELECT request_hash, status_code, content_type, response_body
+             FROM idempotency_keys
+             WHERE user_id = $1 AND key = $2",
+        )
+        .bind(user_id)
+        .bind(key)
+        .fetch_one(&mut *tx)
+        .await?;
+        Some(stored)
+    };
+
+    tx.commit().await?;
+    Ok(existing)
+}
+
+async fn release_key(pool: &PgPool, user_id: Uuid, key: &str) {
+    let released = sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2")
+        .bind(user_id)
+        .bind(key)
+        .execute(pool)
+        .await;
+    if let Err(err) = released {
+        tracing::error!(error = %err, "Failed to release idempotency key");
+    }
+}
@@ -1,30 +1,158 @@
 use axum::{
-    extract::Path,
-    response::Json,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use serde::Serialize;
 use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
 
+use crate::{auth::AuthUser, error::AppError};
+
+// Invite previews carry no per-user data, so shared caches may hold them briefly
+const INVITE_INFO_CACHE_CONTROL: &str = "public, max-age=60";
+
+#[derive(sqlx::FromRow)]
+struct InviteRow {
+    id: Uuid,
+    stream_id: Uuid,
+    role: String,
+    expires_at: Option<OffsetDateTime>,
+    max_uses: Option<i32>,
+    uses_count: i32,
+    revoked_at: Option<OffsetDateTime>,
+}
+
+#[derive(sqlx::FromRow)]
+struct InvitePreviewRow {
+    stream_name: String,
+    role: String,
+    expires_at: Option<OffsetDateTime>,
+    max_uses: Option<i32>,
+    uses_count: i32,
+    revoked_at: Option<OffsetDateTime>,
+}
+
+/// Public invite preview. Deliberately limited to the stream name and granted
+/// role: no owner, member or inviter details.
 #[derive(Serialize)]
-struct InviteResponse {
-    message: String,
-    purpose: String,
+struct InvitePreview {
+    stream_name: String,
+    role: String,
+    valid: bool,
 }
 
-async fn join_stream(Path(code): Path<String>) -> Json<InviteResponse> {
-    Json(InviteResponse {
-        message: format!("Join stream with invite code {} endpoint", code),
-        purpose: "Join stream using invite code, validates expiration and usage limits".to_string(),
-    })
+#[derive(Serialize)]
+struct JoinResponse {
+    stream_id: Uuid,
+    role: String,
+    #[serde(with = "time::serde::rfc3339")]
+    joined_at: OffsetDateTime,
 }
 
-async fn get_invite_info(Path(code): Path<String>) -> Json<InviteResponse> {
-    Json(InviteResponse {
-        message: format!("Get invite info for code {} endpoint", code),
-        purpose: "Get public invite information including stream name and role without joining".to_string(),
-    })
+async fn join_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(code): Path<String>,
+) -> Result<Json<JoinResponse>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    // Row lock serializes concurrent joins so uses_count can't overshoot max_uses
+    let invite = sqlx::query_as::<_, InviteRow>(
+        "SELECT id, stream_id, role, expires_at, max_uses, uses_count, revoked_at
+         FROM invites
+         WHERE code = $1
+         FOR UPDATE",
+    )
+    .bind(&code)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if invite.revoked_at.is_some() {
+        return Err(AppError::Gone("Invite has been revoked".to_string()));
+    }
+    if invite.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err(AppError::Gone("Invite expired".to_string()));
+    }
+
+    let already_member = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM stream_members WHERE stream_id = $1 AND user_id = $2)",
+    )
+    .bind(invite.stream_id)
+    .bind(user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if already_member {
+        return Err(AppError::Conflict("Already a member of this stream".to_string()));
+    }
+    if invite.max_uses.is_some_and(|max_uses| invite.uses_count >= max_uses) {
+        return Err(AppError::Conflict("Usage limit exceeded".to_string()));
+    }
+
+    let joined_at = sqlx::query_scalar::<_, OffsetDateTime>(
+        "INSERT INTO stream_members (stream_id, user_id, role)
+         VALUES ($1, $2, $3)
+         RETURNING joined_at",
+    )
+    .bind(invite.stream_id)
+    .bind(user.user_id)
+    .bind(&invite.role)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE invites SET uses_count = uses_count + 1 WHERE id = $1")
+        .bind(invite.id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT INTO invite_uses (invite_id, user_id) VALUES ($1, $2)")
+        .bind(invite.id)
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(JoinResponse {
+        stream_id: invite.stream_id,
+        role: invite.role,
+        joined_at,
+    }))
+}
+
+async fn get_invite_info(
+    State(pool): State<PgPool>,
+    Path(code): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let invite = sqlx::query_as::<_, InvitePreviewRow>(
+        "SELECT s.name AS stream_name, i.role, i.expires_at, i.max_uses, i.uses_count, i.revoked_at
+         FROM invites i
+         JOIN streams s ON s.id = i.stream_id
+         WHERE i.code = $1",
+    )
+    .bind(&code)
+    .fetch_one(&pool)
+    .await?;
+
+    if invite.revoked_at.is_some() {
+        return Err(AppError::Gone("Invite has been revoked".to_string()));
+    }
+    if invite.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err(AppError::Gone("Invite expired".to_string()));
+    }
+
+    let exhausted = invite.max_uses.is_some_and(|max_uses| invite.uses_count >= max_uses);
+    let preview = InvitePreview {
+        stream_name: invite.stream_name,
+        role: invite.role,
+        valid: !exhausted,
+    };
+
+    Ok(([(header::CACHE_CONTROL, INVITE_INFO_CACHE_CONTROL)], Json(preview)))
 }
 
 pub fn routes() -> Router<PgPool> {
@@ -0,0 +1,302 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    sync::{Arc, LazyLock},
+    time::{Duration, SystemTime},
+};
+use time::OffsetDateTime;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    rustls::{self, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+use uuid::Uuid;
+
+use crate::config::{self, MailTransport, SmtpConfig};
+
+// Covers the whole conversation with the mail server, not each step
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+/// A plain-text email to a single recipient.
+pub struct Message {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    Io(std::io::Error),
+    /// The mail server answered with an unexpected reply.
+    Rejected(String),
+    Timeout,
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Io(err) => write!(f, "{}", err),
+            MailError::Rejected(reply) => write!(f, "Rejected by mail server: {}", reply),
+            MailError::Timeout => write!(f, "Mail server timed out"),
+        }
+    }
+}
+
+impl From<std::io::Error> for MailError {
+    fn from(err: std::io::Error) -> Self {
+        MailError::Io(err)
+    }
+}
+
+impl From<reqwest::Error> for MailError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            MailError::Timeout
+        } else {
+            MailError::Rejected(err.to_string())
+        }
+    }
+}
+
+/// Send `message` through the configured transport (`MAIL_TRANSPORT`).
+/// Slow mail servers are cut off after 30 seconds, so callers on a request
+/// path should spawn this rather than await it.
+pub async fn send(message: &Message) -> Result<(), MailError> {
+    let mail = &config::get().mail;
+    let sent = match &mail.transport {
+        MailTransport::Log => {
+            tracing::info!(to = %message.to, subject = %message.subject, body = %message.body, "Email (not sent; MAIL_TRANSPORT=log)");
+            return Ok(());
+        }
+        MailTransport::Smtp(smtp) => tokio::time::timeout(SEND_TIMEOUT, send_smtp(smtp, &mail.from, message)).await,
+        MailTransport::Ses { region } => tokio::time::timeout(SEND_TIMEOUT, send_ses(region, &mail.from, message)).await,
+    };
+    sent.map_err(|_| MailError::Timeout)?
+}
+
+// Header values come from user input (stream names), so drop anything that
+// could start a new header line
+fn header_value(value: &str) -> String {
+    let value: String = value.chars().filter(|c| !c.is_control()).collect();
+    if value.is_ascii() {
+        value
+    } else {
+        format!("=?utf-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+// The bare addr-spec for `MAIL FROM`, without the display name that
+// `MAIL_FROM` usually carries for the `From:` header
+fn envelope_address(from: &str) -> &str {
+    match from.rsplit_once('<') {
+        Some((_, rest)) => rest.split_once('>').map_or(rest, |(address, _)| address).trim(),
+        None => from.trim(),
+    }
+}
+
+// RFC 5322 message with CRLF line endings, dot-stuffed for SMTP `DATA`
+fn smtp_data(from: &str, message: &Message) -> String {
+    let mut data = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@clipstream>\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        header_value(from),
+        header_value(&message.to),
+        header_value(&message.subject),
+        httpdate::fmt_http_date(SystemTime::now()),
+        Uuid::new_v4()
+    );
+    for line in message.body.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push_str(".\r\n");
+    data
+}
+
+async fn send_smtp(config: &SmtpConfig, from: &str, message: &Message) -> Result<(), MailError> {
+    let tcp = TcpStream::connect((config.host.as_str(), config.port)).await?;
+    if !config.tls {
+        return smtp_session(tcp, config, from, message).await;
+    }
+
+    // Implicit TLS (port 465); STARTTLS isn't supported
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let tls = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| MailError::Rejected(err.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(config.host.clone())
+        .map_err(|_| MailError::Rejected(format!("Invalid SMTP host {:?}", config.host)))?;
+    let stream = TlsConnector::from(Arc::new(tls)).connect(server_name, tcp).await?;
+    smtp_session(stream, config, from, message).await
+}
+
+// Read one (possibly multi-line) reply and check its status class: 2 for
+// `2xx`, 3 for `3xx`
+async fn expect_reply<S: AsyncRead + Unpin>(stream: &mut BufReader<S>, class: u16) -> Result<(), MailError> {
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(MailError::Rejected("connection closed".to_string()));
+        }
+        reply.push_str(&line);
+        // `250-...` continues the reply, `250 ...` ends it
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+    }
+
+    match reply.get(..3).and_then(|code| code.parse::<u16>().ok()) {
+        Some(code) if code / 100 == class => Ok(()),
+        _ => Err(MailError::Rejected(reply.trim_end().to_string())),
+    }
+}
+
+async fn command<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut BufReader<S>,
+    line: &str,
+    class: u16,
+) -> Result<(), MailError> {
+    stream.get_mut().write_all(format!("{}\r\n", line).as_bytes()).await?;
+    stream.get_mut().flush().await?;
+    expect_reply(stream, class).await
+}
+
+async fn smtp_session<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    config: &SmtpConfig,
+    from: &str,
+    message: &Message,
+) -> Result<(), MailError> {
+    let mut stream = BufReader::new(stream);
+    expect_reply(&mut stream, 2).await?;
+    command(&mut stream, "EHLO clipstream", 2).await?;
+
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        let credentials = STANDARD.encode(format!("\0{}\0{}", username, password));
+        command(&mut stream, &format!("AUTH PLAIN {}", credentials), 2).await?;
+    }
+
+    command(&mut stream, &format!("MAIL FROM:<{}>", envelope_address(from)), 2).await?;
+    command(&mut stream, &format!("RCPT TO:<{}>", message.to), 2).await?;
+    command(&mut stream, "DATA", 3).await?;
+    stream.get_mut().write_all(smtp_data(from, message).as_bytes()).await?;
+    expect_reply(&mut stream, 2).await?;
+
+    // The message is accepted at this point; a failed goodbye doesn't matter
+    let _ = command(&mut stream, "QUIT", 2).await;
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// SES v2 `SendEmail`, signed with SigV4 using the same `AWS_*` credential
+// variables as storage
+async fn send_ses(region: &str, from: &str, message: &Message) -> Result<(), MailError> {
+    let access_key = std::env::var("AWS_ACCESS_KEY_ID").unwrap_or_default();
+    let secret_key = std::env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_default();
+    let session_token = std::env::var("AWS_SESSION_TOKEN").ok().filter(|token| !token.is_empty());
+
+    let host = format!("email.{}.amazonaws.com", region);
+    let path = "/v2/email/outbound-emails";
+    let body = serde_json::json!({
+        "FromEmailAddress": from,
+        "Destination": { "ToAddresses": [message.to] },
+        "Content": {
+            "Simple": {
+                "Subject": { "Data": message.subject, "Charset": "UTF-8" },
+                "Body": { "Text": { "Data": message.body, "Charset": "UTF-8" } },
+            }
+        },
+    })
+    .to_string();
+
+    let now = OffsetDateTime::now_utc();
+    let date = format!("{:04}{:02}{:02}", now.year(), now.month() as u8, now.day());
+    let amz_date = format!("{}T{:02}{:02}{:02}Z", date, now.hour(), now.minute(), now.second());
+    let payload_hash = hex::encode(Sha256::digest(body.as_bytes()));
+
+    let mut headers = vec![
+        ("content-type", "application/json".to_string()),
+        ("host", host.clone()),
+        ("x-amz-content-sha256", payload_hash.clone()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = &session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!("POST\n{}\n\n{}\n{}\n{}", path, canonical_headers, signed_headers, payload_hash);
+    let scope = format!("{}/{}/ses/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [region, "ses", "aws4_request"]
+        .iter()
+        .fold(hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), &date), |key, part| {
+            hmac_sha256(&key, part)
+        });
+    let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    );
+
+    let mut request = HTTP_CLIENT
+        .post(format!("https://{}{}", host, path))
+        .header("authorization", authorization)
+        .body(body);
+    for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        Err(MailError::Rejected(format!("{} {}", status, detail.trim())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_address_drops_the_display_name() {
+        assert_eq!(envelope_address("Clipstream <no-reply@clipsstream.com>"), "no-reply@clipsstream.com");
+        assert_eq!(envelope_address("\"Clips <and> more\" <team@example.com>"), "team@example.com");
+        assert_eq!(envelope_address("<bare@example.com>"), "bare@example.com");
+        assert_eq!(envelope_address(" plain@example.com "), "plain@example.com");
+    }
+}
@@ -0,0 +1,159 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{Json, Response},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json as JsonColumn, PgPool};
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+use crate::{
+    audit::{self, Action, Target},
+    auth::AdminUser,
+    error::AppError,
+};
+
+// Row in `settings` holding the flag
+const SETTING_KEY: &str = "maintenance";
+// How quickly other instances notice a toggle
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_RETRY_AFTER_SECS: u64 = 120;
+const MAX_RETRY_AFTER_SECS: u64 = 24 * 60 * 60;
+// Exempt from the block so an admin can always switch it back off
+const TOGGLE_PATH: &str = "/admin/maintenance";
+
+// Checked on every request, so kept in atomics rather than read from the database
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RETRY_AFTER_SECS: AtomicU64 = AtomicU64::new(DEFAULT_RETRY_AFTER_SECS);
+
+#[derive(Serialize, Deserialize)]
+struct MaintenanceSetting {
+    enabled: bool,
+    retry_after_secs: u64,
+}
+
+fn apply(setting: &MaintenanceSetting) {
+    RETRY_AFTER_SECS.store(setting.retry_after_secs, Ordering::Relaxed);
+    ENABLED.store(setting.enabled, Ordering::Relaxed);
+}
+
+/// Keep this instance's copy of the maintenance flag in step with the
+/// database, so a toggle made through any instance reaches all of them.
+/// Runs for the life of the server.
+pub async fn refresh_periodically(pool: PgPool) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let setting = sqlx::query_scalar::<_, JsonColumn<MaintenanceSetting>>(
+            "SELECT value FROM settings WHERE key = $1",
+        )
+        .bind(SETTING_KEY)
+        .fetch_optional(&pool)
+        .await;
+        match setting {
+            Ok(Some(JsonColumn(setting))) => apply(&setting),
+            Ok(None) => ENABLED.store(false, Ordering::Relaxed),
+            // Keep the last known state rather than guessing
+            Err(err) => tracing::warn!(error = %err, "Failed to refresh maintenance flag"),
+        }
+    }
+}
+
+/// Reject everything but reads with `503` while maintenance mode is on.
+pub async fn enforce(request: Request, next: Next) -> Result<Response, AppError> {
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if ENABLED.load(Ordering::Relaxed) && !read_only && request.uri().path() != TOGGLE_PATH {
+        return Err(AppError::ServiceUnavailable(RETRY_AFTER_SECS.load(Ordering::Relaxed)));
+    }
+    Ok(next.run(request).await)
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct MaintenanceStatus {
+    enabled: bool,
+    retry_after_secs: i64,
+    #[serde(with = "time::serde::rfc3339::option")]
+    updated_at: Option<OffsetDateTime>,
+}
+
+async fn get_maintenance(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+) -> Result<Json<MaintenanceStatus>, AppError> {
+    let status = sqlx::query_as::<_, MaintenanceStatus>(
+        "SELECT (value->>'enabled')::boolean AS enabled,
+                (value->>'retry_after_secs')::bigint AS retry_after_secs,
+                updated_at
+         FROM settings WHERE key = $1",
+    )
+    .bind(SETTING_KEY)
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(Json(status.unwrap_or(MaintenanceStatus {
+        enabled: false,
+        retry_after_secs: DEFAULT_RETRY_AFTER_SECS as i64,
+        updated_at: None,
+    })))
+}
+
+#[derive(Deserialize)]
+struct SetMaintenanceRequest {
+    enabled: bool,
+    /// Sent back in `Retry-After` on rejected requests.
+    retry_after_secs: Option<u64>,
+}
+
+async fn set_maintenance(
+    State(pool): State<PgPool>,
+    admin: AdminUser,
+    Json(request): Json<SetMaintenanceRequest>,
+) -> Result<Json<MaintenanceStatus>, AppError> {
+    let retry_after_secs = request.retry_after_secs.unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+    if !(1..=MAX_RETRY_AFTER_SECS).contains(&retry_after_secs) {
+        return Err(AppError::Validation(format!(
+            "retry_after_secs must be between 1 and {}",
+            MAX_RETRY_AFTER_SECS
+        )));
+    }
+
+    let setting = MaintenanceSetting {
+        enabled: request.enabled,
+        retry_after_secs,
+    };
+    let mut tx = pool.begin().await?;
+    let updated_at = sqlx::query_scalar::<_, OffsetDateTime>(
+        "INSERT INTO settings (key, value) VALUES ($1, $2)
+         ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()
+         RETURNING updated_at",
+    )
+    .bind(SETTING_KEY)
+    .bind(JsonColumn(&setting))
+    .fetch_one(&mut *tx)
+    .await?;
+    let details = serde_json::json!({ "enabled": setting.enabled, "retry_after_secs": retry_after_secs });
+    audit::record(&mut *tx, admin.0.user_id, Action::MaintenanceSet, Target::None, details).await?;
+    tx.commit().await?;
+
+    // This instance switches now; the others on their next refresh
+    apply(&setting);
+    tracing::warn!(admin_id = %admin.0.user_id, enabled = setting.enabled, "Maintenance mode changed");
+
+    Ok(Json(MaintenanceStatus {
+        enabled: setting.enabled,
+        retry_after_secs: retry_after_secs as i64,
+        updated_at: Some(updated_at),
+    }))
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new().route(TOGGLE_PATH, get(get_maintenance).put(set_maintenance))
+}
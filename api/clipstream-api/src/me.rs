@@ -0,0 +1,267 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::{Json, Response},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::time::Duration;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    conditional,
+    error::AppError,
+    pagination::{self, Page, PageQuery},
+    processing,
+    query::{Validate, ValidQuery},
+    streams,
+};
+
+const DASHBOARD_STREAMS: i64 = 5;
+const DASHBOARD_UPLOADS: i64 = 10;
+// Short enough that a finished upload shows up on the next visit
+const DASHBOARD_MAX_AGE: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, sqlx::FromRow)]
+struct RecentStream {
+    id: Uuid,
+    name: String,
+    role: String,
+    video_count: i64,
+    /// Latest upload to the stream, or when the caller joined if later.
+    #[serde(with = "time::serde::rfc3339")]
+    last_activity_at: OffsetDateTime,
+}
+
+#[derive(sqlx::FromRow)]
+struct UploadRow {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    processing_index: i32,
+    processing_progress: Option<f64>,
+    created_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct RecentUpload {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    processing_index: i32,
+    status: &'static str,
+    /// Overall pipeline completion, 0-100.
+    progress: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+impl From<UploadRow> for RecentUpload {
+    fn from(row: UploadRow) -> Self {
+        RecentUpload {
+            id: row.id,
+            stream_id: row.stream_id,
+            title: row.title,
+            processing_index: row.processing_index,
+            status: processing::status_name(row.processing_index),
+            progress: processing::progress_percent(row.processing_index, row.processing_progress.unwrap_or(0.0)),
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct DashboardStats {
+    total_videos: i64,
+    total_storage_bytes: i64,
+    likes_received: i64,
+}
+
+#[derive(Serialize)]
+struct Dashboard {
+    recent_streams: Vec<RecentStream>,
+    recent_uploads: Vec<RecentUpload>,
+    stats: DashboardStats,
+}
+
+// Everything the home screen needs in one round trip
+async fn get_dashboard(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let recent_streams = sqlx::query_as::<_, RecentStream>(
+        "SELECT s.id, s.name, m.role,
+                COALESCE(v.video_count, 0) AS video_count,
+                GREATEST(m.joined_at, v.last_upload_at) AS last_activity_at
+         FROM stream_members m
+         JOIN streams s ON s.id = m.stream_id
+         LEFT JOIN (
+             SELECT stream_id, COUNT(*) AS video_count, MAX(created_at) AS last_upload_at
+             FROM videos
+             WHERE deleted_at IS NULL
+               AND stream_id IN (SELECT stream_id FROM stream_members WHERE user_id = $1)
+             GROUP BY stream_id
+         ) v ON v.stream_id = s.id
+         WHERE m.user_id = $1
+         ORDER BY last_activity_at DESC, s.id DESC
+         LIMIT $2",
+    )
+    .bind(user.user_id)
+    .bind(DASHBOARD_STREAMS)
+    .fetch_all(&pool)
+    .await?;
+
+    let recent_uploads = sqlx::query_as::<_, UploadRow>(
+        "SELECT id, stream_id, title, processing_index, processing_progress, created_at
+         FROM videos
+         WHERE uploader_id = $1 AND deleted_at IS NULL
+         ORDER BY created_at DESC, id DESC
+         LIMIT $2",
+    )
+    .bind(user.user_id)
+    .bind(DASHBOARD_UPLOADS)
+    .fetch_all(&pool)
+    .await?;
+
+    let stats = sqlx::query_as::<_, DashboardStats>(
+        "SELECT COUNT(*) AS total_videos,
+                COALESCE(SUM(size_bytes), 0)::bigint AS total_storage_bytes,
+                (SELECT COUNT(*)
+                 FROM video_reactions l
+                 JOIN videos lv ON lv.id = l.video_id
+                 WHERE l.reaction = 'like' AND lv.uploader_id = $1 AND lv.deleted_at IS NULL) AS likes_received
+         FROM videos
+         WHERE uploader_id = $1 AND deleted_at IS NULL",
+    )
+    .bind(user.user_id)
+    .fetch_one(&pool)
+    .await?;
+
+    conditional::respond_json(
+        &headers,
+        DASHBOARD_MAX_AGE,
+        &Dashboard {
+            recent_streams,
+            recent_uploads: recent_uploads.into_iter().map(RecentUpload::from).collect(),
+            stats,
+        },
+    )
+}
+
+#[derive(Deserialize)]
+struct RecentlyViewedQuery {
+    limit: Option<i64>,
+}
+
+impl Validate for RecentlyViewedQuery {}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct ViewedVideo {
+    id: Uuid,
+    stream_id: Uuid,
+    stream_name: String,
+    title: String,
+    duration_seconds: Option<i32>,
+    #[serde(with = "time::serde::rfc3339")]
+    viewed_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct RecentlyViewed {
+    videos: Vec<ViewedVideo>,
+}
+
+async fn get_recently_viewed(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<RecentlyViewedQuery>,
+) -> Result<Json<RecentlyViewed>, AppError> {
+    // Views are kept after leaving a stream; the membership join hides them
+    let videos = sqlx::query_as::<_, ViewedVideo>(
+        "SELECT v.id, v.stream_id, s.name AS stream_name, v.title, v.duration_seconds, vv.viewed_at
+         FROM video_views vv
+         JOIN videos v ON v.id = vv.video_id AND v.deleted_at IS NULL
+         JOIN streams s ON s.id = v.stream_id
+         JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = vv.user_id
+         WHERE vv.user_id = $1
+         ORDER BY vv.viewed_at DESC, v.id DESC
+         LIMIT $2",
+    )
+    .bind(user.user_id)
+    .bind(pagination::page_limit(query.limit))
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(RecentlyViewed { videos }))
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct PendingInvite {
+    #[serde(skip)]
+    id: Uuid,
+    code: String,
+    stream_id: Uuid,
+    stream_name: String,
+    role: String,
+    invited_by_name: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    /// Accept with `POST /invites/{code}/join`.
+    #[sqlx(skip)]
+    join_url: String,
+}
+
+// Invites emailed to the caller's address that they could still accept
+async fn list_pending_invites(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<Page<PendingInvite>>, AppError> {
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    let mut invites = sqlx::query_as::<_, PendingInvite>(
+        "SELECT i.id, i.code, i.stream_id, s.name AS stream_name, i.role,
+                inviter.display_name AS invited_by_name, i.expires_at, i.created_at
+         FROM invites i
+         JOIN streams s ON s.id = i.stream_id
+         JOIN users inviter ON inviter.id = i.created_by
+         WHERE lower(i.email) = lower($5)
+           AND i.revoked_at IS NULL
+           AND (i.expires_at IS NULL OR i.expires_at > NOW())
+           AND (i.max_uses IS NULL OR i.uses_count < i.max_uses)
+           AND NOT EXISTS (
+               SELECT 1 FROM stream_members m WHERE m.stream_id = i.stream_id AND m.user_id = $1
+           )
+           AND ($2::timestamptz IS NULL OR (i.created_at, i.id) < ($2, $3))
+         ORDER BY i.created_at DESC, i.id DESC
+         LIMIT $4",
+    )
+    .bind(user.user_id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .bind(&user.email)
+    .fetch_all(&pool)
+    .await?;
+
+    for invite in &mut invites {
+        invite.join_url = streams::invite_join_url(&invite.code);
+    }
+
+    Ok(Json(pagination::into_page(invites, limit, |invite| (invite.created_at, invite.id))))
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/me/dashboard", get(get_dashboard))
+        .route("/me/recently-viewed", get(get_recently_viewed))
+        .route("/me/invites", get(list_pending_invites))
+}
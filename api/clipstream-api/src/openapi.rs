@@ -0,0 +1,79 @@
+use axum::{
+    response::{Html, Json},
+    routing::get,
+    Router,
+};
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{auth::AuthApi, error::ErrorBody, streams::StreamsApi};
+
+// Swagger UI assets are loaded from the CDN so nothing is bundled at build time
+const SWAGGER_UI_VERSION: &str = "5.17.14";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Clipstream API"),
+    components(schemas(ErrorBody)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Google sign-in and app tokens"),
+        (name = "streams", description = "Streams, members, invites and stream videos"),
+    )
+)]
+struct ApiDoc;
+
+// Registers the `bearer_auth` scheme that authenticated paths refer to
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+            );
+        }
+    }
+}
+
+// Each module documents its own routes; they're merged into one spec here
+static SPEC: LazyLock<utoipa::openapi::OpenApi> = LazyLock::new(|| {
+    let mut spec = ApiDoc::openapi();
+    spec.merge(AuthApi::openapi());
+    spec.merge(StreamsApi::openapi());
+    spec
+});
+
+async fn openapi_json() -> Json<&'static utoipa::openapi::OpenApi> {
+    Json(&SPEC)
+}
+
+async fn swagger_ui() -> Html<String> {
+    Html(format!(
+        r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Clipstream API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@{version}/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@{version}/swagger-ui-bundle.js"></script>
+  <script>SwaggerUIBundle({{ url: "/openapi.json", dom_id: "#swagger-ui" }});</script>
+</body>
+</html>"##,
+        version = SWAGGER_UI_VERSION
+    ))
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(swagger_ui))
+}
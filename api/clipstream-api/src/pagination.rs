@@ -0,0 +1,113 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+use time::OffsetDateTime;
+use utoipa::{
+    openapi::{ArrayBuilder, ObjectBuilder, Ref, RefOr, Schema, SchemaType},
+    IntoParams, ToSchema,
+};
+use uuid::Uuid;
+
+use crate::{error::AppError, query::Validate};
+
+pub(crate) const DEFAULT_PAGE_SIZE: i64 = 20;
+pub(crate) const MAX_PAGE_SIZE: i64 = 100;
+
+/// `limit` and `cursor` for list endpoints that take nothing else; endpoints
+/// with filters declare both fields themselves and use [`page_limit`].
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct PageQuery {
+    /// Page size, 1-100 (default 20).
+    limit: Option<i64>,
+    /// `next_cursor` from the previous page.
+    pub(crate) cursor: Option<String>,
+}
+
+impl PageQuery {
+    pub(crate) fn limit(&self) -> i64 {
+        page_limit(self.limit)
+    }
+}
+
+// Out-of-range limits are clamped and cursors checked when decoded
+impl Validate for PageQuery {}
+
+/// Requested page size, defaulted and clamped to 1..=[`MAX_PAGE_SIZE`].
+pub(crate) fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Response envelope for every paginated list. `next_cursor` is `null` on
+/// the last page.
+#[derive(Serialize)]
+pub(crate) struct Page<T> {
+    pub(crate) items: Vec<T>,
+    pub(crate) next_cursor: Option<String>,
+}
+
+/// OpenAPI schema of a [`Page`] of `T`. Modules implement `ToSchema` for the
+/// `Page<T>`s they return with this, under their own names.
+pub(crate) fn page_schema<'s, T: ToSchema<'s>>() -> RefOr<Schema> {
+    let (item, _) = T::schema();
+    ObjectBuilder::new()
+        .description(Some("Response envelope for every paginated list. `next_cursor` is `null` on\nthe last page."))
+        .property("items", ArrayBuilder::new().items(Ref::from_schema_name(item)))
+        .required("items")
+        .property("next_cursor", ObjectBuilder::new().schema_type(SchemaType::String).nullable(true))
+        .into()
+}
+
+/// Encode a `(key, id)` keyset position as an opaque cursor, for lists
+/// ordered by something other than creation time (rank, like count, ...).
+/// The id breaks ties between rows with the same key.
+pub(crate) fn encode_key_cursor(key: impl Display, id: Uuid) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}:{}", key, id))
+}
+
+/// Decode a cursor made by [`encode_key_cursor`]. Anything that doesn't
+/// decode to a `(key, id)` pair is rejected with `400`.
+pub(crate) fn decode_key_cursor<K: FromStr>(cursor: &str) -> Result<(K, Uuid), AppError> {
+    let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (key, id) = decoded.split_once(':').ok_or_else(invalid)?;
+
+    let key = key.parse::<K>().map_err(|_| invalid())?;
+    let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+    Ok((key, id))
+}
+
+/// Encode a `(created_at, id)` keyset position as an opaque cursor.
+pub(crate) fn encode_cursor(created_at: OffsetDateTime, id: Uuid) -> String {
+    encode_key_cursor(created_at.unix_timestamp_nanos(), id)
+}
+
+pub(crate) fn decode_cursor(cursor: &str) -> Result<(OffsetDateTime, Uuid), AppError> {
+    let (nanos, id) = decode_key_cursor::<i128>(cursor)?;
+    let created_at = OffsetDateTime::from_unix_timestamp_nanos(nanos)
+        .map_err(|_| AppError::BadRequest("Invalid cursor".to_string()))?;
+    Ok((created_at, id))
+}
+
+/// Build a page from rows fetched with `LIMIT limit + 1`: the extra
+/// look-ahead row (if any) is dropped and the last kept row becomes the
+/// next cursor.
+pub(crate) fn into_page<T>(
+    mut items: Vec<T>,
+    limit: i64,
+    position: impl Fn(&T) -> (OffsetDateTime, Uuid),
+) -> Page<T> {
+    let next_cursor = if items.len() as i64 > limit {
+        items.truncate(limit as usize);
+        items.last().map(|item| {
+            let (created_at, id) = position(item);
+            encode_cursor(created_at, id)
+        })
+    } else {
+        None
+    };
+
+    Page { items, next_cursor }
+}
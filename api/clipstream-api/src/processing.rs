@@ -1,30 +1,288 @@
 use axum::{
-    extract::Path,
+    extract::State,
     response::Json,
     routing::get,
     Router,
 };
-use serde::Serialize;
-use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgListener, PgPool};
+use std::{sync::LazyLock, time::Duration};
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    query::{Validate, ValidQuery},
+};
+
+/// `processing_index` value for a video flagged as a duplicate of another.
+pub const DUPLICATE: i32 = -1;
+/// `processing_index` value for an upload whose bytes aren't a supported video.
+pub const REJECTED: i32 = -2;
+/// `processing_index` value for a registered video that hasn't started processing.
+pub const QUEUED: i32 = 0;
+/// `processing_index` value once every pipeline stage has finished.
+pub const READY: i32 = 5;
+
+/// Pipeline stages, in order, for processing_index 1..=4.
+pub const STAGES: [&str; 4] = ["duplicate_detection", "transcoding", "transcription", "analysis"];
+
+// Postgres channel the migration's triggers publish progress on
+const EVENTS_CHANNEL: &str = "processing_events";
+// Events buffered per subscriber before a slow one starts skipping
+const EVENTS_CAPACITY: usize = 1024;
+
+// Windows accepted by `/processing/stats` and the interval each covers
+const STATS_WINDOWS: [(&str, &str); 4] = [("1h", "1 hour"), ("24h", "24 hours"), ("7d", "7 days"), ("30d", "30 days")];
+
+/// Human-readable status for a video's `processing_index`.
+pub fn status_name(processing_index: i32) -> &'static str {
+    match processing_index {
+        DUPLICATE => "duplicate",
+        REJECTED => "rejected",
+        QUEUED => "queued",
+        READY => "ready",
+        stage @ 1..=4 => STAGES[(stage - 1) as usize],
+        _ => "unknown",
+    }
+}
+
+/// Estimate the seconds left in the pipeline for a video currently in
+/// `STAGES[stage]` with `stage_progress` (0.0-1.0) of it done, given the recent
+/// average duration of each stage. Returns `None` when a remaining stage has
+/// no history to estimate from.
+pub fn estimate_remaining_secs(stage: usize, stage_progress: f64, avg_stage_secs: &[Option<f64>; 4]) -> Option<f64> {
+    let current = avg_stage_secs.get(stage).copied().flatten()? * (1.0 - stage_progress.clamp(0.0, 1.0));
+    avg_stage_secs
+        .iter()
+        .skip(stage + 1)
+        .try_fold(current, |total, avg| avg.map(|avg| total + avg))
+}
+
+/// Overall pipeline completion (0-100) for a video at `processing_index`
+/// with `stage_progress` (0.0-1.0) of its current stage done.
+pub fn progress_percent(processing_index: i32, stage_progress: f64) -> f64 {
+    match processing_index {
+        READY => 100.0,
+        1..=4 => {
+            let stage = (processing_index - 1) as usize;
+            (stage as f64 + stage_progress.clamp(0.0, 1.0)) / STAGES.len() as f64 * 100.0
+        }
+        _ => 0.0,
+    }
+}
+
+/// A change in a video's processing state, as published by the database.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProgressEvent {
+    pub video_id: Uuid,
+    pub processing_index: i32,
+    pub progress: Option<f64>,
+    /// The current stage's job failed.
+    pub failed: bool,
+}
+
+static EVENTS: LazyLock<broadcast::Sender<ProgressEvent>> = LazyLock::new(|| broadcast::channel(EVENTS_CAPACITY).0);
+
+/// Receive every processing event from now on; filter by `video_id`.
+pub fn subscribe() -> broadcast::Receiver<ProgressEvent> {
+    EVENTS.subscribe()
+}
+
+/// Forward `processing_events` notifications from Postgres (sent by the
+/// workers' updates to `videos` and `processing_jobs`) to SSE subscribers.
+/// Runs for the life of the server.
+pub async fn listen_for_events(pool: PgPool) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!(error = %err, "Failed to connect processing event listener");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        if let Err(err) = listener.listen(EVENTS_CHANNEL).await {
+            tracing::error!(error = %err, "Failed to listen for processing events");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
+        // `recv` reconnects by itself after a dropped connection
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str::<ProgressEvent>(notification.payload()) {
+                    // Nobody listening is fine
+                    Ok(event) => {
+                        let _ = EVENTS.send(event);
+                    }
+                    Err(err) => tracing::warn!(error = %err, "Malformed processing event"),
+                },
+                Err(err) => {
+                    tracing::warn!(error = %err, "Processing event listener error");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct QueueRow {
+    stage: Option<String>,
+    queued: i64,
+    running: i64,
+    oldest_queued_at: Option<OffsetDateTime>,
+    avg_wait_secs: Option<f64>,
+}
 
 #[derive(Serialize)]
-struct ProcessingResponse {
-    message: String,
-    purpose: String,
+struct StageQueue {
+    stage: String,
+    queued: i64,
+    running: i64,
 }
 
-async fn get_queue_status() -> Json<ProcessingResponse> {
-    Json(ProcessingResponse {
-        message: "Processing queue status endpoint".to_string(),
-        purpose: "Get current processing queue length, active jobs, and estimated wait times (Admin only)".to_string(),
-    })
+#[derive(Serialize)]
+struct QueueStatus {
+    queue_length: i64,
+    active_jobs: i64,
+    avg_wait_seconds_last_hour: Option<f64>,
+    oldest_queued_age_seconds: Option<i64>,
+    stages: Vec<StageQueue>,
 }
 
-async fn get_processing_stats() -> Json<ProcessingResponse> {
-    Json(ProcessingResponse {
-        message: "Processing statistics endpoint".to_string(),
-        purpose: "Get detailed processing statistics, success rates, and performance metrics (Admin only)".to_string(),
-    })
+async fn get_queue_status(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+) -> Result<Json<QueueStatus>, AppError> {
+    // One pass: a row per stage plus the grand total (stage NULL)
+    let rows = sqlx::query_as::<_, QueueRow>(
+        "SELECT stage,
+                COUNT(*) FILTER (WHERE status = 'queued') AS queued,
+                COUNT(*) FILTER (WHERE status = 'running') AS running,
+                MIN(created_at) FILTER (WHERE status = 'queued') AS oldest_queued_at,
+                AVG(EXTRACT(EPOCH FROM started_at - created_at)::float8)
+                    FILTER (WHERE started_at > NOW() - INTERVAL '1 hour') AS avg_wait_secs
+         FROM processing_jobs
+         WHERE status IN ('queued', 'running') OR started_at > NOW() - INTERVAL '1 hour'
+         GROUP BY GROUPING SETS ((stage), ())",
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut status = QueueStatus {
+        queue_length: 0,
+        active_jobs: 0,
+        avg_wait_seconds_last_hour: None,
+        oldest_queued_age_seconds: None,
+        stages: Vec::new(),
+    };
+    for row in rows {
+        match row.stage {
+            Some(stage) => status.stages.push(StageQueue {
+                stage,
+                queued: row.queued,
+                running: row.running,
+            }),
+            None => {
+                status.queue_length = row.queued;
+                status.active_jobs = row.running;
+                status.avg_wait_seconds_last_hour = row.avg_wait_secs;
+                status.oldest_queued_age_seconds = row
+                    .oldest_queued_at
+                    .map(|queued_at| (OffsetDateTime::now_utc() - queued_at).whole_seconds());
+            }
+        }
+    }
+    // Report stages in pipeline order
+    status
+        .stages
+        .sort_by_key(|stage| STAGES.iter().position(|name| *name == stage.stage));
+
+    Ok(Json(status))
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    window: Option<String>,
+}
+
+impl StatsQuery {
+    fn window(&self) -> &str {
+        self.window.as_deref().unwrap_or("24h")
+    }
+
+    // The Postgres interval `window` stands for
+    fn interval(&self) -> Option<&'static str> {
+        STATS_WINDOWS
+            .iter()
+            .find(|(name, _)| *name == self.window())
+            .map(|(_, interval)| *interval)
+    }
+}
+
+impl Validate for StatsQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.interval().is_none() {
+            return Err(AppError::Validation("window must be one of 1h, 24h, 7d, 30d".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct StageStats {
+    stage: String,
+    completed: i64,
+    failed: i64,
+    #[sqlx(skip)]
+    success_rate: Option<f64>,
+    mean_duration_seconds: Option<f64>,
+    p95_duration_seconds: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct ProcessingStats {
+    window: String,
+    stages: Vec<StageStats>,
+}
+
+async fn get_processing_stats(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+    ValidQuery(query): ValidQuery<StatsQuery>,
+) -> Result<Json<ProcessingStats>, AppError> {
+    let interval = query.interval().unwrap_or("24 hours");
+    let window = query.window().to_string();
+
+    // Durations only count completed runs; percentiles are computed in SQL
+    let mut stages = sqlx::query_as::<_, StageStats>(
+        "SELECT stage,
+                COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                AVG(EXTRACT(EPOCH FROM finished_at - started_at)::float8)
+                    FILTER (WHERE status = 'completed') AS mean_duration_seconds,
+                percentile_cont(0.95) WITHIN GROUP (ORDER BY EXTRACT(EPOCH FROM finished_at - started_at)::float8)
+                    FILTER (WHERE status = 'completed') AS p95_duration_seconds
+         FROM processing_jobs
+         WHERE status IN ('completed', 'failed')
+           AND finished_at > NOW() - $1::interval
+         GROUP BY stage",
+    )
+    .bind(interval)
+    .fetch_all(&pool)
+    .await?;
+
+    for stage in &mut stages {
+        let total = stage.completed + stage.failed;
+        stage.success_rate = (total > 0).then(|| stage.completed as f64 / total as f64);
+    }
+    stages.sort_by_key(|stage| STAGES.iter().position(|name| *name == stage.stage));
+
+    Ok(Json(ProcessingStats { window, stages }))
 }
 
 pub fn routes() -> Router<PgPool> {
@@ -32,3 +290,54 @@ pub fn routes() -> Router<PgPool> {
         .route("/processing/queue", get(get_queue_status))
         .route("/processing/stats", get(get_processing_stats))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sample averages: 30s duplicate detection, 10 min transcoding, 2 min transcription, 1 min analysis
+    const AVERAGES: [Option<f64>; 4] = [Some(30.0), Some(600.0), Some(120.0), Some(60.0)];
+
+    #[test]
+    fn queued_video_waits_for_every_stage() {
+        assert_eq!(estimate_remaining_secs(0, 0.0, &AVERAGES), Some(810.0));
+        assert_eq!(progress_percent(QUEUED, 0.0), 0.0);
+    }
+
+    #[test]
+    fn mid_stage_counts_what_is_left_of_it() {
+        // Halfway through transcoding: 300s of it, then transcription and analysis
+        assert_eq!(estimate_remaining_secs(1, 0.5, &AVERAGES), Some(480.0));
+        assert_eq!(estimate_remaining_secs(3, 0.75, &AVERAGES), Some(15.0));
+        assert_eq!(progress_percent(2, 0.5), 37.5);
+        assert_eq!(progress_percent(4, 1.0), 100.0);
+    }
+
+    #[test]
+    fn missing_stage_history_gives_no_estimate() {
+        let no_transcription = [Some(30.0), Some(600.0), None, Some(60.0)];
+        assert_eq!(estimate_remaining_secs(1, 0.5, &no_transcription), None);
+        assert_eq!(estimate_remaining_secs(2, 0.0, &no_transcription), None);
+        // Stages already finished don't need history
+        assert_eq!(estimate_remaining_secs(3, 0.0, &no_transcription), Some(60.0));
+        assert_eq!(estimate_remaining_secs(0, 0.0, &[None; 4]), None);
+    }
+
+    #[test]
+    fn finished_and_rejected_states() {
+        assert_eq!(progress_percent(READY, 0.0), 100.0);
+        assert_eq!(progress_percent(REJECTED, 0.9), 0.0);
+        assert_eq!(progress_percent(DUPLICATE, 0.9), 0.0);
+        assert_eq!(status_name(READY), "ready");
+        assert_eq!(status_name(REJECTED), "rejected");
+    }
+
+    #[test]
+    fn stage_progress_is_clamped() {
+        assert_eq!(progress_percent(1, 1.5), 25.0);
+        assert_eq!(progress_percent(1, -0.5), 0.0);
+        assert_eq!(estimate_remaining_secs(1, 2.0, &AVERAGES), Some(180.0));
+        assert_eq!(estimate_remaining_secs(1, -1.0, &AVERAGES), Some(780.0));
+    }
+}
+
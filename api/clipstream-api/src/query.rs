@@ -0,0 +1,41 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::AppError;
+
+/// Query-string parameters that check their own values once deserialized.
+/// Implemented by every list endpoint's query struct; structs whose fields
+/// are all clamped later (page size, cursor) take the default no-op.
+pub(crate) trait Validate {
+    /// Reject values that parse but make no sense (unknown `sort`, a score
+    /// outside 0-1, ...) with `AppError::Validation`.
+    fn validate(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+/// Like `Query<T>`, but runs [`Validate::validate`] and reports parameters
+/// that don't parse at all (`limit=abc`) as `422` with the offending field,
+/// instead of axum's plain-text `400`.
+pub(crate) struct ValidQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| AppError::Validation(rejection.body_text()))?;
+        query.validate()?;
+        Ok(ValidQuery(query))
+    }
+}
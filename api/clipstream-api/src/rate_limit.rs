@@ -0,0 +1,180 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, Request, State},
+    http::{Extensions, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    auth,
+    config::{self, RateLimit},
+    error::AppError,
+};
+
+// Idle buckets are dropped once the map grows past this many keys
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Route groups that share a limit. Each caller gets one bucket per group.
+#[derive(Clone, Copy)]
+pub enum Group {
+    Search,
+    Upload,
+    Share,
+}
+
+impl Group {
+    fn name(self) -> &'static str {
+        match self {
+            Group::Search => "search",
+            Group::Upload => "upload",
+            Group::Share => "share",
+        }
+    }
+
+    fn limit(self) -> &'static RateLimit {
+        let limits = &config::get().rate_limits;
+        match self {
+            Group::Search => &limits.search,
+            Group::Upload => &limits.upload,
+            Group::Share => &limits.share,
+        }
+    }
+}
+
+/// A bucket's state after trying to take a token from it.
+pub struct Decision {
+    /// Whether a token was taken, i.e. the request may go ahead.
+    pub allowed: bool,
+    /// Whole tokens left.
+    pub remaining: u32,
+    /// How long until the bucket has refilled completely.
+    pub reset: Duration,
+    /// How long until the next token, when none was available.
+    pub retry_after: Duration,
+}
+
+/// Where token buckets live. In memory for now; a shared store (e.g. Redis)
+/// can implement this to limit across instances.
+#[async_trait]
+pub trait BucketStore: Send + Sync {
+    /// Take a token from the bucket at `key` if there is one.
+    async fn take(&self, key: &str, limit: &RateLimit) -> Decision;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+#[derive(Default)]
+pub struct InMemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[async_trait]
+impl BucketStore for InMemoryStore {
+    async fn take(&self, key: &str, limit: &RateLimit) -> Decision {
+        let now = Instant::now();
+        let per_sec = limit.per_minute as f64 / 60.0;
+        let capacity = limit.burst as f64;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            // A bucket idle long enough to refill completely is the same as no bucket
+            let refill = Duration::from_secs_f64(capacity / per_sec.max(f64::EPSILON));
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated_at) < refill);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+        bucket.updated_at = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        // A bucket that never refills is reported as a minute away
+        let wait = |tokens: f64| {
+            if tokens <= 0.0 {
+                Duration::ZERO
+            } else if per_sec > 0.0 {
+                Duration::from_secs_f64(tokens / per_sec)
+            } else {
+                Duration::from_secs(60)
+            }
+        };
+        Decision {
+            allowed,
+            remaining: bucket.tokens.floor() as u32,
+            reset: wait(capacity - bucket.tokens),
+            retry_after: if allowed { Duration::ZERO } else { wait(1.0 - bucket.tokens) },
+        }
+    }
+}
+
+static STORE: LazyLock<Box<dyn BucketStore>> = LazyLock::new(|| Box::new(InMemoryStore::default()));
+
+/// The client's IP: `X-Real-IP` when `TRUST_PROXY_HEADERS` is set (behind
+/// nginx), otherwise the socket peer.
+pub(crate) fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    let forwarded = config::get()
+        .trust_proxy_headers
+        .then(|| headers.get("x-real-ip").and_then(|value| value.to_str().ok()))
+        .flatten();
+    forwarded.map(str::to_string).or_else(|| {
+        extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    })
+}
+
+// Signed-in callers are limited per user (the token is only decoded, not
+// looked up; the handler still authenticates). Everyone else is limited per IP.
+fn caller_key(request: &Request) -> String {
+    if let Some(user_id) = auth::bearer_user_id(request.headers()) {
+        return format!("user:{}", user_id);
+    }
+
+    let ip = client_ip(request.headers(), request.extensions());
+    format!("ip:{}", ip.unwrap_or_default())
+}
+
+// Whole seconds, rounded up so clients never come back too early
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs_f64().ceil() as u64
+}
+
+/// Token-bucket limit for a route group; use with
+/// `middleware::from_fn_with_state(Group::Search, rate_limit::enforce)`.
+/// Every response in the group reports the caller's bucket in
+/// `X-RateLimit-Limit` (the burst size), `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` (seconds until it's full again).
+pub async fn enforce(State(group): State<Group>, request: Request, next: Next) -> Response {
+    let key = format!("{}:{}", group.name(), caller_key(&request));
+    let limit = group.limit();
+    let decision = STORE.take(&key, limit).await;
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        AppError::TooManyRequests(ceil_secs(decision.retry_after)).into_response()
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("x-ratelimit-limit"), HeaderValue::from(limit.burst));
+    headers.insert(HeaderName::from_static("x-ratelimit-remaining"), HeaderValue::from(decision.remaining));
+    headers.insert(HeaderName::from_static("x-ratelimit-reset"), HeaderValue::from(ceil_secs(decision.reset)));
+    response
+}
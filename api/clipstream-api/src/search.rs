@@ -1,33 +1,481 @@
 use axum::{
+    extract::{Path, State},
+    middleware,
     response::Json,
     routing::get,
     Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::{fmt, str::FromStr};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    error::AppError,
+    processing,
+    rate_limit::{self, Group},
+    pagination::{self, Page},
+    query::{Validate, ValidQuery},
+    streams::{self, Member, Role},
+};
+
+const MAX_SUGGESTIONS: i64 = 10;
+const MIN_SUGGESTION_PREFIX_LEN: usize = 2;
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: Option<String>,
+    stream_id: Option<Uuid>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct SearchHit {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    rank: f32,
+    snippet: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+// Shared by every search that needs a non-empty `q`
+fn required_term(q: Option<&str>) -> Result<&str, AppError> {
+    let q = q.map(str::trim).unwrap_or_default();
+    if q.is_empty() {
+        return Err(AppError::Validation("q is required".to_string()));
+    }
+    Ok(q)
+}
+
+impl Validate for SearchQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        required_term(self.q.as_deref()).map(|_| ())
+    }
+}
+
+async fn search_videos(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<SearchQuery>,
+) -> Result<Json<Page<SearchHit>>, AppError> {
+    let q = required_term(query.q.as_deref())?;
+
+    let limit = pagination::page_limit(query.limit);
+    // Results are ordered by rank rather than time, so the cursor is a
+    // `(rank, id)` position instead of the usual `(created_at, id)`
+    let cursor = query.cursor.as_deref().map(pagination::decode_key_cursor::<f32>).transpose()?;
+
+    // Count each search once (not per page) towards the caller's suggestions
+    if cursor.is_none() {
+        record_search(&pool, user.user_id, q).await;
+    }
+
+    // Snippets are only built for the page being returned, after the limit
+    let mut hits = sqlx::query_as::<_, SearchHit>(
+        "WITH hits AS (
+             SELECT v.id, v.stream_id, v.title, v.description, t.full_text, v.created_at, q.query,
+                    ts_rank(v.search_vector || COALESCE(t.search_vector, ''::tsvector), q.query) AS rank
+             FROM videos v
+             CROSS JOIN plainto_tsquery('english', $1) AS q(query)
+             JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $2
+             LEFT JOIN transcripts t ON t.video_id = v.id
+             WHERE (v.search_vector @@ q.query OR t.search_vector @@ q.query)
+               AND ($3::uuid IS NULL OR v.stream_id = $3)
+               AND v.deleted_at IS NULL
+         )
+         SELECT id, stream_id, title, rank, created_at,
+                ts_headline('english', concat_ws(' ', title, description, full_text), query,
+                            'MaxFragments=2, MaxWords=20, MinWords=5') AS snippet
+         FROM hits
+         WHERE ($4::real IS NULL OR (rank, id) < ($4, $5))
+         ORDER BY rank DESC, id DESC
+         LIMIT $6",
+    )
+    .bind(q)
+    .bind(user.user_id)
+    .bind(query.stream_id)
+    .bind(cursor.map(|(rank, _)| rank))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let next_cursor = if hits.len() as i64 > limit {
+        hits.truncate(limit as usize);
+        hits.last().map(|hit| pagination::encode_key_cursor(hit.rank, hit.id))
+    } else {
+        None
+    };
+
+    Ok(Json(Page {
+        items: hits,
+        next_cursor,
+    }))
+}
+
+// Failing to record a search shouldn't fail the search itself
+async fn record_search(pool: &PgPool, user_id: Uuid, q: &str) {
+    let normalized = q.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let recorded = sqlx::query(
+        "INSERT INTO search_queries (user_id, query) VALUES ($1, $2)
+         ON CONFLICT (user_id, query) DO UPDATE
+         SET search_count = search_queries.search_count + 1, last_searched_at = NOW()",
+    )
+    .bind(user_id)
+    .bind(normalized)
+    .execute(pool)
+    .await;
+
+    if let Err(err) = recorded {
+        tracing::warn!(error = %err, "Failed to record search query");
+    }
+}
+
+#[derive(Deserialize)]
+struct SuggestionQuery {
+    prefix: Option<String>,
+}
+
+// Short prefixes get no suggestions rather than an error
+impl Validate for SuggestionQuery {}
+
+#[derive(Serialize)]
+struct Suggestions {
+    suggestions: Vec<String>,
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+async fn search_suggestions(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<SuggestionQuery>,
+) -> Result<Json<Suggestions>, AppError> {
+    let prefix = query.prefix.as_deref().map(str::trim).unwrap_or_default().to_lowercase();
+    if prefix.chars().count() < MIN_SUGGESTION_PREFIX_LEN {
+        return Ok(Json(Suggestions { suggestions: Vec::new() }));
+    }
+
+    // The caller's own past searches and titles in their streams; nothing
+    // another user searched for is suggested. Candidates are scored by
+    // similarity plus a damped popularity term, then deduplicated
+    let suggestions = sqlx::query_scalar::<_, String>(
+        "SELECT suggestion FROM (
+             SELECT DISTINCT ON (lower(suggestion)) suggestion, score
+             FROM (
+                 SELECT query AS suggestion,
+                        similarity(query, $1)::float8 + ln(1 + search_count)::float8 / 10 AS score
+                 FROM search_queries
+                 WHERE user_id = $3 AND query LIKE $2
+                 UNION ALL
+                 SELECT v.title,
+                        similarity(v.title, $1)::float8 + ln(1 + COALESCE(v.views_count, 0))::float8 / 10
+                 FROM videos v
+                 JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $3
+                 WHERE (v.title ILIKE $2 OR v.title % $1) AND v.deleted_at IS NULL
+             ) candidates
+             ORDER BY lower(suggestion), score DESC
+         ) ranked
+         ORDER BY score DESC
+         LIMIT $4",
+    )
+    .bind(&prefix)
+    .bind(format!("{}%", escape_like(&prefix)))
+    .bind(user.user_id)
+    .bind(MAX_SUGGESTIONS)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(Suggestions { suggestions }))
+}
+
+#[derive(Deserialize)]
+struct NameQuery {
+    q: Option<String>,
+    limit: Option<i64>,
+}
+
+impl Validate for NameQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        required_term(self.q.as_deref()).map(|_| ())
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct StreamHit {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    role: String,
+    score: f32,
+}
+
+#[derive(Serialize)]
+struct StreamHits {
+    streams: Vec<StreamHit>,
+}
+
+// Streams the caller belongs to, by fuzzy match on name (weighted higher)
+// or description
+async fn search_streams(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<NameQuery>,
+) -> Result<Json<StreamHits>, AppError> {
+    let q = required_term(query.q.as_deref())?;
+
+    let streams = sqlx::query_as::<_, StreamHit>(
+        "SELECT id, name, description, role, score
+         FROM (
+             SELECT s.id, s.name, s.description, m.role,
+                    GREATEST(word_similarity($1, s.name), word_similarity($1, COALESCE(s.description, '')) / 2) AS score
+             FROM streams s
+             JOIN stream_members m ON m.stream_id = s.id AND m.user_id = $2
+             WHERE s.name %> $1 OR s.name ILIKE $3 OR s.description %> $1 OR s.description ILIKE $3
+         ) hits
+         ORDER BY score DESC, name, id
+         LIMIT $4",
+    )
+    .bind(q)
+    .bind(user.user_id)
+    .bind(format!("%{}%", escape_like(q)))
+    .bind(pagination::page_limit(query.limit))
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(StreamHits { streams }))
+}
 
 #[derive(Serialize)]
-struct SearchResponse {
-    message: String,
-    purpose: String,
+struct MemberHits {
+    members: Vec<Member>,
 }
 
-async fn search_videos() -> Json<SearchResponse> {
-    Json(SearchResponse {
-        message: "Search videos endpoint".to_string(),
-        purpose: "Search videos by content, speech transcript, and metadata with ranking and snippets".to_string(),
-    })
+async fn search_members(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<NameQuery>,
+) -> Result<Json<MemberHits>, AppError> {
+    let q = required_term(query.q.as_deref())?;
+    streams::require_stream_role(&pool, id, user.user_id, Role::Viewer).await?;
+
+    let members = sqlx::query_as::<_, Member>(
+        "SELECT m.user_id, u.display_name, u.avatar_url, m.role,
+                m.user_id = s.owner_id AS is_owner, m.joined_at
+         FROM stream_members m
+         JOIN users u ON u.id = m.user_id
+         JOIN streams s ON s.id = m.stream_id
+         WHERE m.stream_id = $1 AND (u.display_name %> $2 OR u.display_name ILIKE $3)
+         ORDER BY word_similarity($2, u.display_name) DESC, u.display_name, m.user_id
+         LIMIT $4",
+    )
+    .bind(id)
+    .bind(q)
+    .bind(format!("%{}%", escape_like(q)))
+    .bind(pagination::page_limit(query.limit))
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(MemberHits { members }))
+}
+
+#[derive(Clone, Copy)]
+enum TrendingWindow {
+    Day,
+    Week,
+}
+
+impl TrendingWindow {
+    fn parse(window: Option<&str>) -> Option<TrendingWindow> {
+        match window {
+            None | Some("24h") => Some(TrendingWindow::Day),
+            Some("7d") => Some(TrendingWindow::Week),
+            Some(_) => None,
+        }
+    }
+
+    fn seconds(self) -> i64 {
+        match self {
+            TrendingWindow::Day => 24 * 60 * 60,
+            TrendingWindow::Week => 7 * 24 * 60 * 60,
+        }
+    }
 }
 
-async fn search_suggestions() -> Json<SearchResponse> {
-    Json(SearchResponse {
-        message: "Search suggestions endpoint".to_string(),
-        purpose: "Get search term suggestions and autocomplete based on content and popular searches".to_string(),
-    })
+#[derive(Deserialize)]
+struct TrendingQuery {
+    /// `24h` (default) or `7d`.
+    window: Option<String>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+impl Validate for TrendingQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if TrendingWindow::parse(self.window.as_deref()).is_none() {
+            return Err(AppError::Validation("window must be 24h or 7d".to_string()));
+        }
+        Ok(())
+    }
+}
+
+// Scores decay with time, so a cursor also pins the moment they were scored
+// at; later pages are ranked exactly like the first
+struct TrendingKey {
+    as_of: i64,
+    score: f64,
+}
+
+impl fmt::Display for TrendingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.score, self.as_of)
+    }
+}
+
+impl FromStr for TrendingKey {
+    type Err = ();
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let (score, as_of) = key.split_once('@').ok_or(())?;
+        Ok(TrendingKey {
+            as_of: as_of.parse().map_err(|_| ())?,
+            score: score.parse().map_err(|_| ())?,
+        })
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct TrendingVideo {
+    id: Uuid,
+    stream_id: Uuid,
+    stream_name: String,
+    title: String,
+    duration_seconds: Option<i32>,
+    /// Engagement within the window.
+    likes: i64,
+    views: i64,
+    shares: i64,
+    score: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+async fn trending_videos(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<TrendingQuery>,
+) -> Result<Json<Page<TrendingVideo>>, AppError> {
+    // Checked by `validate`
+    let window = TrendingWindow::parse(query.window.as_deref()).unwrap_or(TrendingWindow::Day);
+
+    let limit = pagination::page_limit(query.limit);
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(pagination::decode_key_cursor::<TrendingKey>)
+        .transpose()?;
+    let as_of = match &cursor {
+        Some((key, _)) => key.as_of,
+        None => OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    let as_of = OffsetDateTime::from_unix_timestamp(as_of)
+        .map_err(|_| AppError::BadRequest("Invalid cursor".to_string()))?;
+
+    // Each event in the window scores its weight (like 3, share 5, view 1),
+    // halving every quarter of the window. `video_views` keeps only a
+    // viewer's latest play, so views count distinct recent viewers.
+    let mut videos = sqlx::query_as::<_, TrendingVideo>(
+        "WITH window_events AS (
+             SELECT video_id, created_at AS at, 'like' AS kind
+             FROM video_reactions
+             WHERE reaction = 'like' AND created_at > $3 - make_interval(secs => $4) AND created_at <= $3
+             UNION ALL
+             SELECT video_id, viewed_at, 'view'
+             FROM video_views
+             WHERE viewed_at > $3 - make_interval(secs => $4) AND viewed_at <= $3
+             UNION ALL
+             SELECT video_id, created_at, 'share'
+             FROM shares
+             WHERE created_at > $3 - make_interval(secs => $4) AND created_at <= $3
+         ),
+         scored AS (
+             SELECT e.video_id,
+                    COUNT(*) FILTER (WHERE e.kind = 'like') AS likes,
+                    COUNT(*) FILTER (WHERE e.kind = 'view') AS views,
+                    COUNT(*) FILTER (WHERE e.kind = 'share') AS shares,
+                    SUM(CASE e.kind WHEN 'like' THEN 3 WHEN 'share' THEN 5 ELSE 1 END
+                        * exp(-ln(2) * EXTRACT(EPOCH FROM $3 - e.at)::float8 / ($4 / 4.0))) AS score
+             FROM window_events e
+             JOIN videos v ON v.id = e.video_id AND v.deleted_at IS NULL AND v.processing_index = $2
+             JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $1
+             GROUP BY e.video_id
+         )
+         SELECT v.id, v.stream_id, s.name AS stream_name, v.title, v.duration_seconds,
+                sc.likes, sc.views, sc.shares, sc.score, v.created_at
+         FROM scored sc
+         JOIN videos v ON v.id = sc.video_id
+         JOIN streams s ON s.id = v.stream_id
+         WHERE ($5::float8 IS NULL OR (sc.score, v.id) < ($5, $6))
+         ORDER BY sc.score DESC, v.id DESC
+         LIMIT $7",
+    )
+    .bind(user.user_id)
+    .bind(processing::READY)
+    .bind(as_of)
+    .bind(window.seconds() as f64)
+    .bind(cursor.as_ref().map(|(key, _)| key.score))
+    .bind(cursor.as_ref().map(|(_, id)| *id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let next_cursor = if videos.len() as i64 > limit {
+        videos.truncate(limit as usize);
+        videos.last().map(|video| {
+            let key = TrendingKey {
+                as_of: as_of.unix_timestamp(),
+                score: video.score,
+            };
+            pagination::encode_key_cursor(key, video.id)
+        })
+    } else {
+        None
+    };
+
+    Ok(Json(Page {
+        items: videos,
+        next_cursor,
+    }))
 }
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
-        .route("/search", get(search_videos))
-        .route("/search/suggestions", get(search_suggestions))
+        .route(
+            "/videos/trending",
+            get(trending_videos).layer(middleware::from_fn_with_state(Group::Search, rate_limit::enforce)),
+        )
+        .route(
+            "/search",
+            get(search_videos).layer(middleware::from_fn_with_state(Group::Search, rate_limit::enforce)),
+        )
+        .route(
+            "/search/suggestions",
+            get(search_suggestions).layer(middleware::from_fn_with_state(Group::Search, rate_limit::enforce)),
+        )
+        .route(
+            "/search/streams",
+            get(search_streams).layer(middleware::from_fn_with_state(Group::Search, rate_limit::enforce)),
+        )
+        .route(
+            "/streams/:id/members/search",
+            get(search_members).layer(middleware::from_fn_with_state(Group::Search, rate_limit::enforce)),
+        )
 }
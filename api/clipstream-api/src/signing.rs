@@ -0,0 +1,225 @@
+use axum::http::Uri;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use crate::{
+    config::{self, SigningKey},
+    error::AppError,
+    storage,
+};
+
+/// What a signed URL is for; each has its own lifetime setting.
+#[derive(Clone, Copy, Debug)]
+pub enum Purpose {
+    /// Direct uploads to storage (`UPLOAD_URL_TTL_SECS`).
+    Upload,
+    /// Playback and downloads for stream members (`STREAM_URL_TTL_SECS`).
+    Stream,
+    /// Playback through a public share link (`SHARE_URL_TTL_SECS`).
+    Share,
+}
+
+impl Purpose {
+    pub fn ttl(self) -> Duration {
+        let config = config::get();
+        match self {
+            Purpose::Upload => config.upload_url_ttl,
+            Purpose::Stream => config.stream_url_ttl,
+            Purpose::Share => config.share_url_ttl,
+        }
+    }
+}
+
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: OffsetDateTime,
+}
+
+fn signed_url(url: Result<String, object_store::Error>, purpose: Purpose) -> Result<SignedUrl, AppError> {
+    let url = url.map_err(|err| {
+        tracing::error!(error = %err, "Failed to presign storage URL");
+        AppError::Internal
+    })?;
+    Ok(SignedUrl {
+        url,
+        expires_at: OffsetDateTime::now_utc() + purpose.ttl(),
+    })
+}
+
+/// Presigned storage URL to `GET` the object at `key`. These are signed with
+/// the storage credentials, not [`SigningKey`]s.
+pub async fn storage_get(key: &str, purpose: Purpose) -> Result<SignedUrl, AppError> {
+    signed_url(storage::presign_get(key, purpose.ttl()).await, purpose)
+}
+
+/// Presigned storage URL to `PUT` the object at `key`.
+pub async fn storage_put(key: &str, purpose: Purpose) -> Result<SignedUrl, AppError> {
+    signed_url(storage::presign_put(key, purpose.ttl()).await, purpose)
+}
+
+/// Absolute URL for an API path (which may carry its own query) that can be
+/// fetched without an app token until it expires. Everything in
+/// `path_and_query` is covered by the signature, so it can carry claims such
+/// as the viewer's id.
+pub fn sign_path(path_and_query: &str, purpose: Purpose) -> SignedUrl {
+    let config = config::get();
+    let expires_at = OffsetDateTime::now_utc() + purpose.ttl();
+    let signed = sign_with(&config.signing_keys[0], path_and_query, expires_at.unix_timestamp());
+    SignedUrl {
+        url: format!("{}{}", config.public_api_url.trim_end_matches('/'), signed),
+        expires_at,
+    }
+}
+
+/// Check the signature on a request made with a URL from [`sign_path`].
+pub fn verify(uri: &Uri) -> Result<(), AppError> {
+    let path_and_query = uri.path_and_query().map(|value| value.as_str()).unwrap_or_default();
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    verify_with(&config::get().signing_keys, path_and_query, now).map_err(|err| match err {
+        SignatureError::Missing => AppError::Unauthorized,
+        err => {
+            tracing::debug!(error = ?err, "Rejected signed URL");
+            AppError::Forbidden
+        }
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SignatureError {
+    /// No `expires`/`kid`/`sig` parameters at all.
+    Missing,
+    Malformed,
+    /// Signed with a key that has since been retired.
+    UnknownKey,
+    BadSignature,
+    Expired,
+}
+
+fn mac(key: &SigningKey, message: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(message.as_bytes());
+    mac
+}
+
+// Appends `expires` and `kid`, then a `sig` over everything before it
+fn sign_with(key: &SigningKey, path_and_query: &str, expires: i64) -> String {
+    let separator = if path_and_query.contains('?') { '&' } else { '?' };
+    let message = format!("{}{}expires={}&kid={}", path_and_query, separator, expires, key.id);
+    let signature = hex::encode(mac(key, &message).finalize().into_bytes());
+    format!("{}&sig={}", message, signature)
+}
+
+fn verify_with(keys: &[SigningKey], path_and_query: &str, now: i64) -> Result<(), SignatureError> {
+    // `sig` is always last, so the signed message is everything before it
+    let (message, signature) = path_and_query.rsplit_once("&sig=").ok_or(SignatureError::Missing)?;
+    let query = message.split_once('?').map(|(_, query)| query).ok_or(SignatureError::Missing)?;
+
+    let (mut expires, mut kid) = (None, None);
+    for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match name {
+            "expires" => expires = value.parse::<i64>().ok(),
+            "kid" => kid = Some(value),
+            _ => {}
+        }
+    }
+    let (expires, kid) = expires.zip(kid).ok_or(SignatureError::Malformed)?;
+
+    let key = keys.iter().find(|key| key.id == kid).ok_or(SignatureError::UnknownKey)?;
+    let signature = hex::decode(signature).map_err(|_| SignatureError::Malformed)?;
+    mac(key, message)
+        .verify_slice(&signature)
+        .map_err(|_| SignatureError::BadSignature)?;
+
+    if expires <= now {
+        return Err(SignatureError::Expired);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_signing_keys;
+
+    const NOW: i64 = 1_754_000_000;
+    const PATH: &str = "/files/videos/5f0c7a52-3a4e-4a8e-9a59-0c8e1a0e6b11/stream?viewer=0b7e3c1d-7a3f-4c55-8f4c-2a7d1b9e4f60";
+
+    fn keys(raw: &str) -> Vec<SigningKey> {
+        parse_signing_keys(raw).expect("valid SIGNING_KEYS")
+    }
+
+    #[test]
+    fn verifies_with_primary_key() {
+        let keys = keys("k2:new-secret,k1:old-secret");
+        let url = sign_with(&keys[0], PATH, NOW + 60);
+        assert_eq!(verify_with(&keys, &url, NOW), Ok(()));
+    }
+
+    #[test]
+    fn rotated_out_key_still_verifies() {
+        let before = keys("k1:old-secret");
+        let url = sign_with(&before[0], PATH, NOW + 60);
+
+        let after = keys("k2:new-secret,k1:old-secret");
+        assert_eq!(verify_with(&after, &url, NOW), Ok(()));
+    }
+
+    #[test]
+    fn retired_key_is_rejected() {
+        let before = keys("k1:old-secret");
+        let url = sign_with(&before[0], PATH, NOW + 60);
+
+        let after = keys("k2:new-secret");
+        assert_eq!(verify_with(&after, &url, NOW), Err(SignatureError::UnknownKey));
+    }
+
+    #[test]
+    fn expired_url_is_rejected() {
+        let keys = keys("k2:new-secret,k1:old-secret");
+        let url = sign_with(&keys[1], PATH, NOW - 1);
+        assert_eq!(verify_with(&keys, &url, NOW), Err(SignatureError::Expired));
+    }
+
+    #[test]
+    fn tampered_url_is_rejected() {
+        let keys = keys("k1:secret");
+        let url = sign_with(&keys[0], PATH, NOW + 60);
+
+        let other_viewer = url.replace("viewer=0b7e", "viewer=1b7e");
+        assert_eq!(verify_with(&keys, &other_viewer, NOW), Err(SignatureError::BadSignature));
+        let extended = url.replace(&format!("expires={}", NOW + 60), &format!("expires={}", NOW + 6000));
+        assert_eq!(verify_with(&keys, &extended, NOW), Err(SignatureError::BadSignature));
+    }
+
+    #[test]
+    fn same_key_id_with_another_secret_is_rejected() {
+        let url = sign_with(&keys("k1:leaked")[0], PATH, NOW + 60);
+        assert_eq!(verify_with(&keys("k1:secret"), &url, NOW), Err(SignatureError::BadSignature));
+    }
+
+    #[test]
+    fn unsigned_paths_are_missing_a_signature() {
+        let keys = keys("k1:secret");
+        assert_eq!(verify_with(&keys, PATH, NOW), Err(SignatureError::Missing));
+        assert_eq!(verify_with(&keys, "/files/videos/x/stream", NOW), Err(SignatureError::Missing));
+        assert_eq!(
+            verify_with(&keys, "/files/videos/x/stream?expires=soon&kid=k1&sig=00", NOW),
+            Err(SignatureError::Malformed)
+        );
+    }
+
+    #[test]
+    fn parses_signing_keys() {
+        let keys = keys(" k2:new-secret , k1:old:secret ,");
+        let ids: Vec<_> = keys.iter().map(|key| key.id.as_str()).collect();
+        assert_eq!(ids, ["k2", "k1"]);
+        assert_eq!(keys[1].secret, "old:secret");
+
+        assert!(parse_signing_keys("").unwrap().is_empty());
+        for invalid in ["k1", "k1:", ":secret", "k 1:secret", "k1:a,k1:b"] {
+            assert!(parse_signing_keys(invalid).is_err(), "{:?} should be rejected", invalid);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+/// How many leading bytes [`video_container`] needs to decide.
+pub const SNIFF_BYTES: usize = 4096;
+
+// ISO base media brands that are still images rather than video
+const IMAGE_BRANDS: [&[u8; 4]; 6] = [b"heic", b"heix", b"mif1", b"msf1", b"avif", b"avis"];
+// Top-level atoms a QuickTime file from before `ftyp` existed may start with
+const LEGACY_QUICKTIME_ATOMS: [&[u8; 4]; 6] = [b"moov", b"mdat", b"wide", b"free", b"skip", b"pnot"];
+
+const EBML_MAGIC: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+const EBML_DOC_TYPE_ID: [u8; 2] = [0x42, 0x82];
+
+/// A video container we accept for processing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Container {
+    Mp4,
+    QuickTime,
+    /// WebM, or the Matroska it is a subset of.
+    WebM,
+}
+
+impl Container {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Container::Mp4 => "video/mp4",
+            Container::QuickTime => "video/quicktime",
+            Container::WebM => "video/webm",
+        }
+    }
+}
+
+/// The container `head` (the first [`SNIFF_BYTES`] of a file, or all of a
+/// shorter one) belongs to, or `None` if it isn't one we accept. Judged from
+/// the bytes alone, so a renamed zip or image uploaded as `video/mp4` is
+/// caught before it reaches the workers.
+pub fn video_container(head: &[u8]) -> Option<Container> {
+    if head.starts_with(&EBML_MAGIC) {
+        return match ebml_doc_type(head)? {
+            b"webm" | b"matroska" => Some(Container::WebM),
+            _ => None,
+        };
+    }
+
+    let size = u32::from_be_bytes(head.get(..4)?.try_into().ok()?);
+    let box_type: &[u8; 4] = head.get(4..8)?.try_into().ok()?;
+    if box_type == b"ftyp" {
+        let brand: &[u8; 4] = head.get(8..12)?.try_into().ok()?;
+        return match brand {
+            _ if size < 16 => None,
+            b"qt  " => Some(Container::QuickTime),
+            _ if IMAGE_BRANDS.contains(&brand) => None,
+            _ => Some(Container::Mp4),
+        };
+    }
+
+    // Size 0 runs to the end of the file and 1 means a 64-bit size follows
+    let valid_size = matches!(size, 0 | 1) || size >= 8;
+    (valid_size && LEGACY_QUICKTIME_ATOMS.contains(&box_type)).then_some(Container::QuickTime)
+}
+
+// Length of an EBML variable-length integer, from the leading zeros of its
+// first byte
+fn vint_len(first: u8) -> Option<usize> {
+    let len = first.leading_zeros() as usize + 1;
+    (len <= 8).then_some(len)
+}
+
+// Decode an EBML size: its length in bytes and the value without the marker bit
+fn vint(bytes: &[u8]) -> Option<(usize, u64)> {
+    let len = vint_len(*bytes.first()?)?;
+    let value = bytes
+        .get(..len)?
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64);
+    Some((len, value & (u64::MAX >> (64 - 7 * len))))
+}
+
+// Walk the EBML header's child elements to find `DocType`
+fn ebml_doc_type(head: &[u8]) -> Option<&[u8]> {
+    let rest = head.strip_prefix(&EBML_MAGIC)?;
+    let (size_len, header_size) = vint(rest)?;
+    let mut header = &rest[size_len..];
+    header = &header[..header.len().min(usize::try_from(header_size).ok()?)];
+
+    while !header.is_empty() {
+        let id_len = vint_len(header[0])?;
+        let id = header.get(..id_len)?;
+        let (size_len, size) = vint(&header[id_len..])?;
+        let start = id_len + size_len;
+        let end = start.checked_add(usize::try_from(size).ok()?)?;
+        let body = header.get(start..end)?;
+        if id == EBML_DOC_TYPE_ID {
+            return Some(body);
+        }
+        header = &header[end..];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MP4: &[u8] = b"\x00\x00\x00\x20ftypisom\x00\x00\x02\x00isomiso2avc1mp41\x00\x00\x00\x08free";
+    const MP4_IPHONE: &[u8] = b"\x00\x00\x00\x1cftypmp42\x00\x00\x00\x01mp41mp42isom\x00\x00\x00\x08wide";
+    const QUICKTIME: &[u8] = b"\x00\x00\x00\x14ftypqt  \x00\x00\x02\x00qt  \x00\x00\x00\x08wide";
+    const QUICKTIME_LEGACY: &[u8] = b"\x00\x00\x00\x08wide\x00\x01\x2c\x3emdat";
+    const WEBM: &[u8] = b"\x1a\x45\xdf\xa3\x9f\x42\x86\x81\x01\x42\xf7\x81\x01\x42\xf2\x81\x04\x42\xf3\x81\x08\x42\x82\x84webm\x42\x87\x81\x04\x42\x85\x81\x02\x18\x53\x80\x67";
+    const MATROSKA: &[u8] = b"\x1a\x45\xdf\xa3\xa3\x42\x86\x81\x01\x42\xf7\x81\x01\x42\xf2\x81\x04\x42\xf3\x81\x08\x42\x82\x88matroska\x42\x87\x81\x04\x42\x85\x81\x02";
+    const EBML_OTHER: &[u8] = b"\x1a\x45\xdf\xa3\x8b\x42\x86\x81\x01\x42\x82\x84abcd";
+    const ZIP: &[u8] = b"PK\x03\x04\x14\x00\x00\x00\x08\x00\x00\x00!\x00video.mp4";
+    const HEIC: &[u8] = b"\x00\x00\x00\x18ftypheic\x00\x00\x00\x00mif1heic";
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+    const TEXT: &[u8] = b"<html><body>not a video</body></html>";
+
+    #[test]
+    fn accepts_iso_base_media() {
+        assert_eq!(video_container(MP4), Some(Container::Mp4));
+        assert_eq!(video_container(MP4_IPHONE), Some(Container::Mp4));
+        assert_eq!(video_container(QUICKTIME), Some(Container::QuickTime));
+        assert_eq!(video_container(QUICKTIME_LEGACY), Some(Container::QuickTime));
+    }
+
+    #[test]
+    fn accepts_webm_and_matroska() {
+        assert_eq!(video_container(WEBM), Some(Container::WebM));
+        assert_eq!(video_container(MATROSKA), Some(Container::WebM));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        for head in [ZIP, HEIC, PNG, TEXT, EBML_OTHER] {
+            assert_eq!(video_container(head), None);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_and_truncated_files() {
+        assert_eq!(video_container(b""), None);
+        assert_eq!(video_container(&MP4[..10]), None);
+        assert_eq!(video_container(&WEBM[..20]), None);
+        assert_eq!(video_container(b"\x00\x00\x00\x04wide"), None);
+    }
+
+    #[test]
+    fn rejects_undersized_ftyp() {
+        assert_eq!(video_container(b"\x00\x00\x00\x0cftypisom"), None);
+    }
+}
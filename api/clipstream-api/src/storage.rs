@@ -0,0 +1,126 @@
+use axum::{body::Bytes, http::Method};
+use futures::{stream::BoxStream, TryStreamExt};
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    signer::Signer,
+    Attribute, GetOptions, GetRange, ObjectMeta, ObjectStore,
+};
+use std::{ops::Range, sync::OnceLock, time::Duration};
+
+use crate::config::AppConfig;
+
+pub type ByteStream = BoxStream<'static, Result<Bytes, object_store::Error>>;
+
+static STORE: OnceLock<AmazonS3> = OnceLock::new();
+
+/// Build the S3 client from the configured bucket, region and endpoint (set
+/// the endpoint to point at MinIO locally). Credentials come from the standard
+/// `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` variables.
+pub fn init(config: &AppConfig) -> Result<(), object_store::Error> {
+    let mut builder = AmazonS3Builder::from_env()
+        .with_bucket_name(&config.s3_bucket)
+        .with_region(&config.s3_region);
+
+    if let Some(endpoint) = &config.s3_endpoint {
+        builder = builder
+            .with_allow_http(endpoint.starts_with("http://"))
+            .with_endpoint(endpoint);
+    }
+
+    let _ = STORE.set(builder.build()?);
+    Ok(())
+}
+
+fn store() -> &'static AmazonS3 {
+    STORE.get().expect("storage::init must be called at startup")
+}
+
+/// Check the bucket is reachable with the configured credentials by listing
+/// an (empty) prefix: one small request. A missing bucket or rejected
+/// credentials are errors.
+pub async fn ping() -> Result<(), object_store::Error> {
+    store().list_with_delimiter(Some(&Path::from("health-check"))).await?;
+    Ok(())
+}
+
+/// Presigned URL that lets a client `PUT` an object directly to storage.
+pub async fn presign_put(key: &str, ttl: Duration) -> Result<String, object_store::Error> {
+    let url = store().signed_url(Method::PUT, &Path::from(key), ttl).await?;
+    Ok(url.to_string())
+}
+
+/// Presigned URL that lets a client `GET` an object until `ttl` elapses.
+pub async fn presign_get(key: &str, ttl: Duration) -> Result<String, object_store::Error> {
+    let url = store().signed_url(Method::GET, &Path::from(key), ttl).await?;
+    Ok(url.to_string())
+}
+
+/// Metadata (size, ETag, last modified) for the object at `key`.
+pub async fn head(key: &str) -> Result<ObjectMeta, object_store::Error> {
+    store().head(&Path::from(key)).await
+}
+
+/// Metadata for the object at `key` plus the `Content-Type` it was stored with.
+pub async fn head_with_content_type(key: &str) -> Result<(ObjectMeta, Option<String>), object_store::Error> {
+    let options = GetOptions {
+        head: true,
+        ..Default::default()
+    };
+    let result = store().get_opts(&Path::from(key), options).await?;
+    let content_type = result.attributes.get(&Attribute::ContentType).map(|value| value.to_string());
+    Ok((result.meta, content_type))
+}
+
+/// The bytes of the object at `key` within `range`, read into memory; meant
+/// for small reads such as a file's header.
+pub async fn get_range(key: &str, range: Range<u64>) -> Result<Bytes, object_store::Error> {
+    store()
+        .get_range(&Path::from(key), range.start as usize..range.end as usize)
+        .await
+}
+
+/// The whole object at `key`, read into memory; meant for small objects such
+/// as playlists.
+pub async fn get(key: &str) -> Result<Bytes, object_store::Error> {
+    store().get(&Path::from(key)).await?.bytes().await
+}
+
+/// Stream the object at `key`, or only the bytes in `range`, without
+/// buffering it in memory.
+pub async fn get_stream(key: &str, range: Option<Range<u64>>) -> Result<ByteStream, object_store::Error> {
+    let options = GetOptions {
+        range: range.map(|range| GetRange::Bounded(range.start as usize..range.end as usize)),
+        ..Default::default()
+    };
+    let result = store().get_opts(&Path::from(key), options).await?;
+    Ok(result.into_stream())
+}
+
+async fn delete_object_or_prefix(key: &str) -> Result<(), object_store::Error> {
+    let store = store();
+    if key.ends_with('/') {
+        let objects: Vec<_> = store.list(Some(&Path::from(key))).try_collect().await?;
+        for object in objects {
+            store.delete(&object.location).await?;
+        }
+        Ok(())
+    } else {
+        match store.delete(&Path::from(key)).await {
+            Err(object_store::Error::NotFound { .. }) => Ok(()),
+            result => result,
+        }
+    }
+}
+
+/// Delete each key (keys ending in `/` delete everything under that prefix)
+/// and return the ones that couldn't be deleted.
+pub async fn delete_objects(keys: Vec<String>) -> Vec<String> {
+    let mut failed = Vec::new();
+    for key in keys {
+        if delete_object_or_prefix(&key).await.is_err() {
+            failed.push(key);
+        }
+    }
+    failed
+}
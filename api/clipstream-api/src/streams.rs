@@ -1,123 +1,2120 @@
 use axum::{
-    extract::Path,
-    response::Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    middleware,
+    response::{IntoResponse, Json, Response},
     routing::{delete, get, post, put},
     Router,
 };
-use serde::Serialize;
-use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, PgExecutor, PgPool};
+use std::{collections::HashMap, fmt, str::FromStr};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use utoipa::{
+    openapi::{RefOr, Schema},
+    IntoParams, OpenApi, ToSchema,
+};
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Target},
+    auth::AuthUser,
+    codes,
+    conditional::Precondition,
+    config,
+    error::AppError,
+    mailer,
+    pagination::{self, decode_cursor, into_page, Page, PageQuery},
+    processing,
+    query::{Validate, ValidQuery},
+    rate_limit::{self, Group},
+    signing::{self, Purpose},
+    videos,
+};
+
+const MAX_NAME_LEN: usize = 100;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+
+const INVITE_CODE_LEN: usize = 10;
+const MAX_BULK_MEMBERS: usize = 100;
+const MAX_EMAIL_LEN: usize = 255;
 
-#[derive(Serialize)]
-struct StreamResponse {
-    message: String,
-    purpose: String,
+/// A member's role within a stream, ordered from least to most privileged.
+/// Ownership is tracked separately on `streams.owner_id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Creator,
+    Admin,
 }
 
-async fn list_streams() -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: "List streams endpoint".to_string(),
-        purpose: "List user's streams with role information, member counts, and video counts".to_string(),
-    })
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Creator => "creator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Role> {
+        match role {
+            "viewer" => Some(Role::Viewer),
+            "creator" => Some(Role::Creator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
 }
 
-async fn create_stream() -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: "Create stream endpoint".to_string(),
-        purpose: "Create a new stream with name, description, and initial settings".to_string(),
-    })
+/// The caller's standing in a stream, as returned by [`require_stream_role`].
+pub struct StreamAccess {
+    pub role: Role,
+    pub is_owner: bool,
 }
 
-async fn get_stream(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Get stream {} endpoint", id),
-        purpose: "Get detailed stream information including settings, owner, and user's role".to_string(),
-    })
+/// Check that `user_id` is a member of `stream_id` with at least `min_role`.
+/// The owner always passes. Returns `NotFound` for an unknown stream and
+/// `Forbidden` for non-members or insufficient roles.
+pub(crate) async fn require_stream_role(
+    executor: impl PgExecutor<'_>,
+    stream_id: Uuid,
+    user_id: Uuid,
+    min_role: Role,
+) -> Result<StreamAccess, AppError> {
+    let (is_owner, role) = sqlx::query_as::<_, (bool, Option<String>)>(
+        "SELECT s.owner_id = $2, m.role
+         FROM streams s
+         LEFT JOIN stream_members m ON m.stream_id = s.id AND m.user_id = $2
+         WHERE s.id = $1",
+    )
+    .bind(stream_id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let role = role.as_deref().and_then(Role::parse).ok_or(AppError::Forbidden)?;
+    if !is_owner && role < min_role {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(StreamAccess { role, is_owner })
 }
 
-async fn update_stream(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Update stream {} endpoint", id),
-        purpose: "Update stream settings, name, description, and other metadata".to_string(),
-    })
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct StreamSummary {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    role: String,
+    member_count: i64,
+    video_count: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
 }
 
-async fn delete_stream(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Delete stream {} endpoint", id),
-        purpose: "Delete stream and all associated videos and member data".to_string(),
-    })
+#[utoipa::path(
+    get,
+    path = "/streams",
+    tag = "streams",
+    params(PageQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Streams the caller belongs to, newest first", body = StreamPage),
+        (status = 400, description = "Invalid cursor", body = ErrorBody),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+    )
+)]
+async fn list_streams(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<Page<StreamSummary>>, AppError> {
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(decode_cursor).transpose()?;
+
+    let streams = sqlx::query_as::<_, StreamSummary>(
+        "SELECT s.id, s.name, s.description, m.role, s.created_at,
+                mc.member_count, vc.video_count
+         FROM streams s
+         JOIN stream_members m ON m.stream_id = s.id AND m.user_id = $1
+         LEFT JOIN LATERAL (
+             SELECT COUNT(*) AS member_count FROM stream_members WHERE stream_id = s.id
+         ) mc ON TRUE
+         LEFT JOIN LATERAL (
+             SELECT COUNT(*) AS video_count FROM videos WHERE stream_id = s.id AND deleted_at IS NULL
+         ) vc ON TRUE
+         WHERE $2::timestamptz IS NULL OR (s.created_at, s.id) < ($2, $3)
+         ORDER BY s.created_at DESC, s.id DESC
+         LIMIT $4",
+    )
+    .bind(user.user_id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(into_page(streams, limit, |stream| (stream.created_at, stream.id))))
 }
 
-async fn list_members(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("List members of stream {} endpoint", id),
-        purpose: "List all members of the stream with their roles and join dates".to_string(),
-    })
+#[derive(Deserialize, ToSchema)]
+struct CreateStreamRequest {
+    name: String,
+    description: Option<String>,
 }
 
-async fn update_member(Path((id, user_id)): Path<(String, String)>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Update member {} role in stream {} endpoint", user_id, id),
-        purpose: "Update member role (creator/viewer) in the stream".to_string(),
-    })
+#[derive(Deserialize, ToSchema)]
+struct UpdateStreamRequest {
+    name: Option<String>,
+    description: Option<String>,
+    settings: Option<serde_json::Value>,
+    /// Global admins only.
+    storage_quota_bytes: Option<i64>,
 }
 
-async fn remove_member(Path((id, user_id)): Path<(String, String)>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Remove member {} from stream {} endpoint", user_id, id),
-        purpose: "Remove member from stream and revoke access to all videos".to_string(),
-    })
+impl UpdateStreamRequest {
+    fn only_sets_quota(&self) -> bool {
+        self.storage_quota_bytes.is_some() && self.name.is_none() && self.description.is_none() && self.settings.is_none()
+    }
 }
 
-async fn list_invites(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("List invites for stream {} endpoint", id),
-        purpose: "List active invite links with codes, roles, and usage statistics (Admin only)".to_string(),
-    })
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct StreamRecord {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    owner_id: Uuid,
+    is_private: bool,
+    settings: serde_json::Value,
+    /// Send back in `If-Match` when updating.
+    version: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
 }
 
-async fn create_invite(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Create invite for stream {} endpoint", id),
-        purpose: "Create new invite link with role, expiration, and usage limits (Admin only)".to_string(),
-    })
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "name must be between 1 and {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    Ok(name)
 }
 
-async fn get_invite(Path((id, code)): Path<(String, String)>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Get invite {} details for stream {} endpoint", code, id),
-        purpose: "Get detailed invite information including usage statistics (Admin only)".to_string(),
-    })
+fn validate_description(description: &str) -> Result<(), AppError> {
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(AppError::Validation(format!(
+            "description must be at most {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    Ok(())
 }
 
-async fn update_invite(Path((id, code)): Path<(String, String)>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Update invite {} settings for stream {} endpoint", code, id),
-        purpose: "Update invite settings like expiration date and usage limits (Admin only)".to_string(),
-    })
+#[utoipa::path(
+    post,
+    path = "/streams",
+    tag = "streams",
+    request_body = CreateStreamRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Stream created; the caller is its owner", body = StreamRecord),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 422, description = "Invalid name or description", body = ErrorBody),
+    )
+)]
+async fn create_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Json(request): Json<CreateStreamRequest>,
+) -> Result<(StatusCode, Json<StreamRecord>), AppError> {
+    let name = validate_name(&request.name)?;
+    if let Some(description) = &request.description {
+        validate_description(description)?;
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let stream = sqlx::query_as::<_, StreamRecord>(
+        "INSERT INTO streams (name, description, owner_id)
+         VALUES ($1, $2, $3)
+         RETURNING id, name, description, owner_id, is_private, settings, version, created_at, updated_at",
+    )
+    .bind(name)
+    .bind(&request.description)
+    .bind(user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Ownership lives on the stream row; the owner is also a regular creator member
+    sqlx::query("INSERT INTO stream_members (stream_id, user_id, role) VALUES ($1, $2, 'creator')")
+        .bind(stream.id)
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(stream)))
 }
 
-async fn revoke_invite(Path((id, code)): Path<(String, String)>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Revoke invite {} for stream {} endpoint", code, id),
-        purpose: "Revoke invite link and prevent further usage (Admin only)".to_string(),
-    })
+#[derive(sqlx::FromRow)]
+struct StreamRow {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    is_private: bool,
+    settings: serde_json::Value,
+    version: i32,
+    created_at: OffsetDateTime,
+    owner_id: Uuid,
+    owner_name: String,
+    owner_avatar_url: Option<String>,
+    member_count: i64,
 }
 
-async fn list_videos(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("List videos in stream {} endpoint", id),
-        purpose: "List all videos in stream with metadata, processing status, and thumbnails".to_string(),
-    })
+#[derive(Serialize, ToSchema)]
+struct StreamOwner {
+    id: Uuid,
+    display_name: String,
+    avatar_url: Option<String>,
 }
 
-async fn upload_video(Path(id): Path<String>) -> Json<StreamResponse> {
-    Json(StreamResponse {
-        message: format!("Upload video to stream {} endpoint", id),
-        purpose: "Upload video file or get presigned URL for large files, triggers processing pipeline".to_string(),
-    })
+#[derive(Serialize, ToSchema)]
+struct StreamDetails {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    is_private: bool,
+    settings: serde_json::Value,
+    owner: StreamOwner,
+    user_role: Role,
+    is_owner: bool,
+    member_count: i64,
+    /// Send back in `If-Match` when updating.
+    version: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
 }
 
+#[utoipa::path(
+    get,
+    path = "/streams/{id}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Stream details and the caller's role", body = StreamDetails),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Not a member", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+    )
+)]
+async fn get_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StreamDetails>, AppError> {
+    let access = require_stream_role(&pool, id, user.user_id, Role::Viewer).await?;
+
+    let stream = sqlx::query_as::<_, StreamRow>(
+        "SELECT s.id, s.name, s.description, s.is_private, s.settings, s.version, s.created_at,
+                u.id AS owner_id, u.display_name AS owner_name, u.avatar_url AS owner_avatar_url,
+                (SELECT COUNT(*) FROM stream_members WHERE stream_id = s.id) AS member_count
+         FROM streams s
+         JOIN users u ON u.id = s.owner_id
+         WHERE s.id = $1",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(StreamDetails {
+        id: stream.id,
+        name: stream.name,
+        description: stream.description,
+        is_private: stream.is_private,
+        settings: stream.settings,
+        owner: StreamOwner {
+            id: stream.owner_id,
+            display_name: stream.owner_name,
+            avatar_url: stream.owner_avatar_url,
+        },
+        user_role: access.role,
+        is_owner: access.is_owner,
+        member_count: stream.member_count,
+        version: stream.version,
+        created_at: stream.created_at,
+    }))
+}
+
+#[derive(Serialize, ToSchema)]
+struct MyRole {
+    role: Role,
+    is_owner: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/my-role",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The caller's role in the stream", body = MyRole),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 404, description = "Stream not found, or the caller is not a member", body = ErrorBody),
+    )
+)]
+async fn get_my_role(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MyRole>, AppError> {
+    // Any member passes the viewer check, so `Forbidden` here only means
+    // "not a member", which this endpoint reports as 404
+    let access = require_stream_role(&pool, id, user.user_id, Role::Viewer)
+        .await
+        .map_err(|err| match err {
+            AppError::Forbidden => AppError::NotFound,
+            err => err,
+        })?;
+
+    Ok(Json(MyRole {
+        role: access.role,
+        is_owner: access.is_owner,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/streams/{id}",
+    tag = "streams",
+    params(
+        ("id" = Uuid, Path, description = "Stream id"),
+        ("If-Match" = String, Header, description = "The stream's current `version` (or `*` to overwrite)"),
+    ),
+    request_body = UpdateStreamRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated stream", body = StreamRecord),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires creator role (global admins sending only a quota excepted)", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 412, description = "The stream was changed since `version`", body = ErrorBody),
+        (status = 422, description = "Invalid name, description, settings or quota", body = ErrorBody),
+        (status = 428, description = "Missing If-Match header", body = ErrorBody),
+    )
+)]
+async fn update_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateStreamRequest>,
+) -> Result<Json<StreamRecord>, AppError> {
+    let precondition = Precondition::from_headers(&headers)?;
+    let name = request.name.as_deref().map(validate_name).transpose()?;
+    if let Some(description) = &request.description {
+        validate_description(description)?;
+    }
+    if let Some(settings) = &request.settings
+        && !settings.is_object()
+    {
+        return Err(AppError::Validation("settings must be a JSON object".to_string()));
+    }
+    if let Some(quota) = request.storage_quota_bytes {
+        if !user.is_admin {
+            return Err(AppError::Forbidden);
+        }
+        if quota < 1 {
+            return Err(AppError::Validation("storage_quota_bytes must be positive".to_string()));
+        }
+    }
+
+    // Global admins can set a quota on any stream, but editing anything else
+    // still takes creator role in it
+    let mut tx = pool.begin().await?;
+    if !(user.is_admin && request.only_sets_quota()) {
+        require_stream_role(&mut *tx, id, user.user_id, Role::Creator).await?;
+    }
+
+    // Checked under the row lock so two edits from the same version can't both win
+    let (version, previous_quota) = sqlx::query_as::<_, (i32, Option<i64>)>(
+        "SELECT version, storage_quota_bytes FROM streams WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    precondition.check(version)?;
+
+    if let Some(quota) = request.storage_quota_bytes {
+        let details = serde_json::json!({ "storage_quota_bytes": quota, "previous_quota_bytes": previous_quota });
+        audit::record(&mut *tx, user.user_id, Action::StreamQuotaSet, Target::Stream(id), details).await?;
+    }
+
+    // Omitted fields keep their current values
+    let stream = sqlx::query_as::<_, StreamRecord>(
+        "UPDATE streams
+         SET name = COALESCE($2, name),
+             description = COALESCE($3, description),
+             settings = COALESCE($4, settings),
+             storage_quota_bytes = COALESCE($5, storage_quota_bytes),
+             version = version + 1,
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, name, description, owner_id, is_private, settings, version, created_at, updated_at",
+    )
+    .bind(id)
+    .bind(name)
+    .bind(&request.description)
+    .bind(&request.settings)
+    .bind(request.storage_quota_bytes)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Json(stream))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{id}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Stream and its videos deleted"),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Only the owner can delete a stream", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+    )
+)]
+async fn delete_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let access = require_stream_role(&mut *tx, id, user.user_id, Role::Viewer).await?;
+    if !access.is_owner {
+        return Err(AppError::Forbidden);
+    }
+
+    let deleted_videos = sqlx::query_as::<_, (Uuid, Option<String>, Option<String>)>(
+        "DELETE FROM videos WHERE stream_id = $1 RETURNING id, storage_path, thumbnail_path",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM stream_members WHERE stream_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM invites WHERE stream_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM streams WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Queue the stream's objects for removal so files aren't orphaned in storage
+    let keys: Vec<String> = deleted_videos
+        .into_iter()
+        .flat_map(|(video_id, storage_path, thumbnail_path)| {
+            videos::storage_keys(video_id, storage_path, thumbnail_path)
+        })
+        .collect();
+    sqlx::query("INSERT INTO storage_deletions (object_key) SELECT UNNEST($1::text[])")
+        .bind(&keys)
+        .execute(&mut *tx)
+        .await?;
+
+    // Dropping `tx` on any earlier error rolls everything back
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The new owner is raised to creator, except that an admin keeps admin:
+/// lowering an admin's role because they took ownership would take away
+/// their member management.
+#[derive(Deserialize, ToSchema)]
+struct TransferStreamRequest {
+    /// An existing member of the stream.
+    new_owner_user_id: Uuid,
+    /// Make the previous owner a viewer; otherwise they keep their role.
+    #[serde(default)]
+    demote_previous_owner: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{id}/transfer",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    request_body = TransferStreamRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Ownership moved to the new owner", body = StreamRecord),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Only the owner can transfer a stream", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 422, description = "New owner isn't a member, or already owns the stream", body = ErrorBody),
+    )
+)]
+async fn transfer_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<TransferStreamRequest>,
+) -> Result<Json<StreamRecord>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    // Owner checked under the row lock so two transfers can't both go through
+    let owner_id = sqlx::query_scalar::<_, Uuid>("SELECT owner_id FROM streams WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if owner_id != user.user_id {
+        return Err(AppError::Forbidden);
+    }
+    if request.new_owner_user_id == owner_id {
+        return Err(AppError::Validation("new_owner_user_id already owns this stream".to_string()));
+    }
+
+    // Raised to creator at least; an admin stays an admin
+    let promoted = sqlx::query(
+        "UPDATE stream_members SET role = CASE WHEN role = $4 THEN role ELSE $3 END
+         WHERE stream_id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(request.new_owner_user_id)
+    .bind(Role::Creator.as_str())
+    .bind(Role::Admin.as_str())
+    .execute(&mut *tx)
+    .await?;
+    if promoted.rows_affected() == 0 {
+        return Err(AppError::Validation(
+            "new_owner_user_id must be a member of the stream".to_string(),
+        ));
+    }
+
+    if request.demote_previous_owner {
+        sqlx::query("UPDATE stream_members SET role = $3 WHERE stream_id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(owner_id)
+            .bind(Role::Viewer.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let stream = sqlx::query_as::<_, StreamRecord>(
+        "UPDATE streams
+         SET owner_id = $2, version = version + 1, updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, name, description, owner_id, is_private, settings, version, created_at, updated_at",
+    )
+    .bind(id)
+    .bind(request.new_owner_user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Json(stream))
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+pub(crate) struct Member {
+    user_id: Uuid,
+    display_name: String,
+    avatar_url: Option<String>,
+    role: String,
+    is_owner: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    joined_at: OffsetDateTime,
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/members",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), PageQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Members in join order", body = MemberPage),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Not a member", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+    )
+)]
+async fn list_members(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<Page<Member>>, AppError> {
+    require_stream_role(&pool, id, user.user_id, Role::Viewer).await?;
+
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(decode_cursor).transpose()?;
+
+    let members = sqlx::query_as::<_, Member>(
+        "SELECT m.user_id, u.display_name, u.avatar_url, m.role,
+                m.user_id = s.owner_id AS is_owner, m.joined_at
+         FROM stream_members m
+         JOIN users u ON u.id = m.user_id
+         JOIN streams s ON s.id = m.stream_id
+         WHERE m.stream_id = $1
+           AND ($2::timestamptz IS NULL OR (m.joined_at, m.user_id) > ($2, $3))
+         ORDER BY m.joined_at ASC, m.user_id ASC
+         LIMIT $4",
+    )
+    .bind(id)
+    .bind(cursor.map(|(joined_at, _)| joined_at))
+    .bind(cursor.map(|(_, user_id)| user_id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(into_page(members, limit, |member| (member.joined_at, member.user_id))))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct UpdateMemberRequest {
+    role: String,
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct Membership {
+    stream_id: Uuid,
+    user_id: Uuid,
+    role: String,
+    #[serde(with = "time::serde::rfc3339")]
+    joined_at: OffsetDateTime,
+}
+
+#[utoipa::path(
+    put,
+    path = "/streams/{id}/members/{user_id}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("user_id" = Uuid, Path, description = "Member user id")),
+    request_body = UpdateMemberRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated membership", body = Membership),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream or member not found", body = ErrorBody),
+        (status = 422, description = "role must be creator or viewer, or target is the owner", body = ErrorBody),
+    )
+)]
+async fn update_member(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateMemberRequest>,
+) -> Result<Json<Membership>, AppError> {
+    let role = match Role::parse(&request.role) {
+        Some(role @ (Role::Creator | Role::Viewer)) => role,
+        _ => return Err(AppError::Validation("role must be creator or viewer".to_string())),
+    };
+
+    let mut tx = pool.begin().await?;
+    require_stream_role(&mut *tx, id, user.user_id, Role::Admin).await?;
+
+    // Lock the membership so a concurrent removal can't race this update
+    let target_is_owner = sqlx::query_scalar::<_, bool>(
+        "SELECT s.owner_id = m.user_id
+         FROM stream_members m
+         JOIN streams s ON s.id = m.stream_id
+         WHERE m.stream_id = $1 AND m.user_id = $2
+         FOR UPDATE OF m",
+    )
+    .bind(id)
+    .bind(member_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if target_is_owner {
+        return Err(AppError::Validation("cannot change owner role".to_string()));
+    }
+
+    let membership = sqlx::query_as::<_, Membership>(
+        "UPDATE stream_members SET role = $3
+         WHERE stream_id = $1 AND user_id = $2
+         RETURNING stream_id, user_id, role, joined_at",
+    )
+    .bind(id)
+    .bind(member_id)
+    .bind(role.as_str())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(membership))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{id}/members/{user_id}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("user_id" = Uuid, Path, description = "Member user id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Member removed (or left)"),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Removing someone else requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream or member not found", body = ErrorBody),
+        (status = 422, description = "Target is the owner", body = ErrorBody),
+    )
+)]
+async fn remove_member(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let mut tx = pool.begin().await?;
+
+    // Any member may leave; removing someone else needs admin rights
+    let min_role = if member_id == user.user_id { Role::Viewer } else { Role::Admin };
+    require_stream_role(&mut *tx, id, user.user_id, min_role).await?;
+
+    let target_is_owner = sqlx::query_scalar::<_, bool>(
+        "SELECT s.owner_id = m.user_id
+         FROM stream_members m
+         JOIN streams s ON s.id = m.stream_id
+         WHERE m.stream_id = $1 AND m.user_id = $2
+         FOR UPDATE OF m",
+    )
+    .bind(id)
+    .bind(member_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if target_is_owner {
+        return Err(AppError::Validation("cannot remove the stream owner".to_string()));
+    }
+
+    sqlx::query("DELETE FROM stream_members WHERE stream_id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Share links outlive membership otherwise, so revoke the ones they made here
+    sqlx::query(
+        "UPDATE shares SET revoked_at = NOW()
+         WHERE created_by = $2
+           AND revoked_at IS NULL
+           AND video_id IN (SELECT id FROM videos WHERE stream_id = $1)",
+    )
+    .bind(id)
+    .bind(member_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, ToSchema)]
+struct BulkMembersRequest {
+    /// Up to 100 addresses.
+    emails: Vec<String>,
+    /// `creator` or `viewer`.
+    role: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum BulkMemberStatus {
+    /// Had an account and is now a member.
+    Added,
+    /// No account yet; an invite for this address was created (or already pending).
+    Invited,
+    AlreadyMember,
+    Error,
+}
+
+#[derive(Serialize, ToSchema)]
+struct BulkMemberResult {
+    email: String,
+    status: BulkMemberStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    join_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct BulkMembersResponse {
+    results: Vec<BulkMemberResult>,
+}
+
+// Just enough to catch typos and pasted junk; delivery is the real test
+fn is_plausible_email(email: &str) -> bool {
+    email.len() <= MAX_EMAIL_LEN
+        && !email.chars().any(char::is_whitespace)
+        && email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.') && !domain.contains('@'))
+}
+
+// Adds an existing user or invites the address. Runs inside the caller's
+// savepoint, so a failure only discards this email's changes.
+async fn add_member_by_email(
+    conn: &mut sqlx::PgConnection,
+    stream_id: Uuid,
+    email: &str,
+    role: Role,
+    invited_by: Uuid,
+) -> Result<BulkMemberResult, AppError> {
+    let result = |status, join_url| BulkMemberResult {
+        email: email.to_string(),
+        status,
+        join_url,
+        error: None,
+    };
+
+    let existing = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT u.id, m.user_id IS NOT NULL
+         FROM users u
+         LEFT JOIN stream_members m ON m.stream_id = $2 AND m.user_id = u.id
+         WHERE lower(u.email) = lower($1)",
+    )
+    .bind(email)
+    .bind(stream_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match existing {
+        Some((_, true)) => return Ok(result(BulkMemberStatus::AlreadyMember, None)),
+        Some((user_id, false)) => {
+            sqlx::query("INSERT INTO stream_members (stream_id, user_id, role) VALUES ($1, $2, $3)")
+                .bind(stream_id)
+                .bind(user_id)
+                .bind(role.as_str())
+                .execute(&mut *conn)
+                .await?;
+            return Ok(result(BulkMemberStatus::Added, None));
+        }
+        None => {}
+    }
+
+    // Importing the same list twice shouldn't pile up invites
+    let pending = sqlx::query_scalar::<_, String>(
+        "SELECT code FROM invites
+         WHERE stream_id = $1 AND lower(email) = lower($2)
+           AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > NOW())
+           AND (max_uses IS NULL OR uses_count < max_uses)
+         LIMIT 1",
+    )
+    .bind(stream_id)
+    .bind(email)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(code) = pending {
+        return Ok(result(BulkMemberStatus::Invited, Some(invite_join_url(&code))));
+    }
+
+    // A code collision inside a transaction would abort it, so skip the row
+    // instead of erroring and try another code
+    for _ in 0..codes::MAX_CODE_ATTEMPTS {
+        let code = sqlx::query_scalar::<_, String>(
+            "INSERT INTO invites (code, stream_id, role, created_by, max_uses, email)
+             VALUES ($1, $2, $3, $4, 1, $5)
+             ON CONFLICT (code) DO NOTHING
+             RETURNING code",
+        )
+        .bind(codes::generate_code(INVITE_CODE_LEN))
+        .bind(stream_id)
+        .bind(role.as_str())
+        .bind(invited_by)
+        .bind(email)
+        .fetch_optional(&mut *conn)
+        .await?;
+        if let Some(code) = code {
+            return Ok(result(BulkMemberStatus::Invited, Some(invite_join_url(&code))));
+        }
+    }
+    Err(AppError::Internal)
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{id}/members/bulk",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    request_body = BulkMembersRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Outcome for each distinct email, in request order", body = BulkMembersResponse),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 422, description = "Invalid role, or no emails / more than 100", body = ErrorBody),
+    )
+)]
+async fn bulk_add_members(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<BulkMembersRequest>,
+) -> Result<Json<BulkMembersResponse>, AppError> {
+    let role = match Role::parse(&request.role) {
+        Some(role @ (Role::Creator | Role::Viewer)) => role,
+        _ => return Err(AppError::Validation("role must be creator or viewer".to_string())),
+    };
+
+    let mut emails: Vec<String> = Vec::with_capacity(request.emails.len());
+    for email in request.emails {
+        let email = email.trim().to_string();
+        if !emails.iter().any(|seen| seen.eq_ignore_ascii_case(&email)) {
+            emails.push(email);
+        }
+    }
+    if emails.is_empty() || emails.len() > MAX_BULK_MEMBERS {
+        return Err(AppError::Validation(format!(
+            "emails must contain between 1 and {} addresses",
+            MAX_BULK_MEMBERS
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    require_stream_role(&mut *tx, id, user.user_id, Role::Admin).await?;
+
+    // Each email gets its own savepoint: one failure is reported in its
+    // result and rolled back without losing the rest of the batch
+    let mut results = Vec::with_capacity(emails.len());
+    for email in emails {
+        if !is_plausible_email(&email) {
+            results.push(BulkMemberResult {
+                email,
+                status: BulkMemberStatus::Error,
+                join_url: None,
+                error: Some("Invalid email address".to_string()),
+            });
+            continue;
+        }
+
+        let mut savepoint = tx.begin().await?;
+        match add_member_by_email(&mut savepoint, id, &email, role, user.user_id).await {
+            Ok(result) => {
+                savepoint.commit().await?;
+                results.push(result);
+            }
+            Err(err) => {
+                savepoint.rollback().await?;
+                if let AppError::Database(err) = &err {
+                    tracing::error!(error = %err, email = %email, "Bulk member import failed for email");
+                }
+                results.push(BulkMemberResult {
+                    email,
+                    status: BulkMemberStatus::Error,
+                    join_url: None,
+                    error: Some(err.to_string()),
+                });
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(Json(BulkMembersResponse { results }))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct CreateInviteRequest {
+    role: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    max_uses: Option<i32>,
+    /// Also email the join link to this address.
+    email: Option<String>,
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct Invite {
+    #[serde(skip)]
+    id: Uuid,
+    code: String,
+    role: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    max_uses: Option<i32>,
+    uses_count: i32,
+    /// Address the join link was emailed to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    /// Whether the mail server accepted the email; `null` while it's still
+    /// being sent, and when there's no `email`.
+    email_delivered: Option<bool>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+impl Invite {
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc())
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.max_uses.is_some_and(|max_uses| self.uses_count >= max_uses)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct InviteStatus {
+    #[serde(flatten)]
+    invite: Invite,
+    expired: bool,
+    exhausted: bool,
+}
+
+impl From<Invite> for InviteStatus {
+    fn from(invite: Invite) -> Self {
+        InviteStatus {
+            expired: invite.is_expired(),
+            exhausted: invite.is_exhausted(),
+            invite,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct CreatedInvite {
+    #[serde(flatten)]
+    invite: Invite,
+    join_url: String,
+}
+
+pub(crate) fn invite_join_url(code: &str) -> String {
+    let base = &config::get().public_web_url;
+    format!("{}/invites/{}", base.trim_end_matches('/'), code)
+}
+
+// Sends from a spawned task that records the outcome on the invite, so the
+// request never waits on the mail server
+fn spawn_invite_email(pool: &PgPool, invite: &Invite, invited_by: Uuid, stream_id: Uuid, join_url: String) {
+    let Some(to) = invite.email.clone() else {
+        return;
+    };
+    let pool = pool.clone();
+    let invite_id = invite.id;
+    let role = invite.role.clone();
+    let expires_at = invite.expires_at;
+
+    tokio::spawn(async move {
+        let sent = async {
+            let (stream_name, inviter) = sqlx::query_as::<_, (String, String)>(
+                "SELECT s.name, u.display_name FROM streams s, users u WHERE s.id = $1 AND u.id = $2",
+            )
+            .bind(stream_id)
+            .bind(invited_by)
+            .fetch_one(&pool)
+            .await
+            .map_err(|err| {
+                tracing::error!(invite_id = %invite_id, error = %err, "Failed to load invite email details");
+                "Failed to load invite details".to_string()
+            })?;
+
+            let expiry = match expires_at.and_then(|expires_at| expires_at.format(&Rfc3339).ok()) {
+                Some(expires_at) => format!("This invite expires at {}.\n", expires_at),
+                None => String::new(),
+            };
+            let message = mailer::Message {
+                to,
+                subject: format!("{} invited you to {} on Clipstream", inviter, stream_name),
+                body: format!(
+                    "{} invited you to join the stream \"{}\" as a {}.\n\nJoin here: {}\n{}",
+                    inviter, stream_name, role, join_url, expiry
+                ),
+            };
+            mailer::send(&message).await.map_err(|err| {
+                tracing::warn!(invite_id = %invite_id, error = %err, "Failed to send invite email");
+                err.to_string()
+            })
+        }
+        .await;
+
+        let recorded = sqlx::query("UPDATE invites SET email_delivered = $2, email_error = $3 WHERE id = $1")
+            .bind(invite_id)
+            .bind(sent.is_ok())
+            .bind(sent.err())
+            .execute(&pool)
+            .await;
+        if let Err(err) = recorded {
+            tracing::error!(invite_id = %invite_id, error = %err, "Failed to record invite email delivery");
+        }
+    });
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{id}/invites",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    request_body = CreateInviteRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Invite created", body = CreatedInvite),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 422, description = "Invalid role, expires_at, max_uses or email", body = ErrorBody),
+    )
+)]
+async fn create_invite(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<CreatedInvite>), AppError> {
+    let role = match Role::parse(&request.role) {
+        Some(role @ (Role::Creator | Role::Viewer)) => role,
+        _ => return Err(AppError::Validation("role must be creator or viewer".to_string())),
+    };
+    if request.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err(AppError::Validation("expires_at must be in the future".to_string()));
+    }
+    if request.max_uses.is_some_and(|max_uses| max_uses < 1) {
+        return Err(AppError::Validation("max_uses must be at least 1".to_string()));
+    }
+    let email = request.email.as_deref().map(str::trim).filter(|email| !email.is_empty());
+    if email.is_some_and(|email| !is_plausible_email(email)) {
+        return Err(AppError::Validation("email is not a valid address".to_string()));
+    }
+
+    require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    // Codes are random, so a collision is rare; just try again with a new one
+    for _ in 0..codes::MAX_CODE_ATTEMPTS {
+        let inserted = sqlx::query_as::<_, Invite>(
+            "INSERT INTO invites (code, stream_id, role, created_by, expires_at, max_uses, email)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id, code, role, expires_at, max_uses, uses_count, email, email_delivered, created_at",
+        )
+        .bind(codes::generate_code(INVITE_CODE_LEN))
+        .bind(id)
+        .bind(role.as_str())
+        .bind(user.user_id)
+        .bind(request.expires_at)
+        .bind(request.max_uses)
+        .bind(email)
+        .fetch_one(&pool)
+        .await;
+
+        match inserted {
+            Ok(invite) => {
+                let join_url = invite_join_url(&invite.code);
+                spawn_invite_email(&pool, &invite, user.user_id, id, join_url.clone());
+                return Ok((StatusCode::CREATED, Json(CreatedInvite { invite, join_url })));
+            }
+            Err(err) if codes::is_unique_violation(&err) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Err(AppError::Internal)
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/invites",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), PageQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Invites, newest first", body = InvitePage),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+    )
+)]
+async fn list_invites(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<Page<InviteStatus>>, AppError> {
+    require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(decode_cursor).transpose()?;
+
+    let invites = sqlx::query_as::<_, Invite>(
+        "SELECT id, code, role, expires_at, max_uses, uses_count, email, email_delivered, created_at
+         FROM invites
+         WHERE stream_id = $1
+           AND revoked_at IS NULL
+           AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4",
+    )
+    .bind(id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, invite_id)| invite_id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let page = into_page(invites, limit, |invite| (invite.created_at, invite.id));
+    Ok(Json(Page {
+        items: page.items.into_iter().map(InviteStatus::from).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct InviteUse {
+    user_id: Uuid,
+    display_name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    used_at: OffsetDateTime,
+}
+
+#[derive(Serialize, ToSchema)]
+struct InviteDetails {
+    #[serde(flatten)]
+    status: InviteStatus,
+    join_url: String,
+    uses: Vec<InviteUse>,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct UpdateInviteRequest {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    max_uses: Option<i32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/invites/{code}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("code" = String, Path, description = "Invite code")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Invite with its uses", body = InviteDetails),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream or invite not found", body = ErrorBody),
+    )
+)]
+async fn get_invite(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, code)): Path<(Uuid, String)>,
+) -> Result<Json<InviteDetails>, AppError> {
+    require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    let invite = sqlx::query_as::<_, Invite>(
+        "SELECT id, code, role, expires_at, max_uses, uses_count, email, email_delivered, created_at
+         FROM invites
+         WHERE stream_id = $1 AND code = $2",
+    )
+    .bind(id)
+    .bind(&code)
+    .fetch_one(&pool)
+    .await?;
+
+    let uses = sqlx::query_as::<_, InviteUse>(
+        "SELECT iu.user_id, u.display_name, iu.used_at
+         FROM invite_uses iu
+         JOIN users u ON u.id = iu.user_id
+         WHERE iu.invite_id = $1
+         ORDER BY iu.used_at ASC",
+    )
+    .bind(invite.id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(InviteDetails {
+        join_url: invite_join_url(&invite.code),
+        status: invite.into(),
+        uses,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/streams/{id}/invites/{code}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("code" = String, Path, description = "Invite code")),
+    request_body = UpdateInviteRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Updated invite", body = InviteStatus),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream or invite not found", body = ErrorBody),
+        (status = 422, description = "Invalid expires_at or max_uses", body = ErrorBody),
+    )
+)]
+async fn update_invite(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, code)): Path<(Uuid, String)>,
+    Json(request): Json<UpdateInviteRequest>,
+) -> Result<Json<InviteStatus>, AppError> {
+    if request.expires_at.is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc()) {
+        return Err(AppError::Validation("expires_at must be in the future".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+    require_stream_role(&mut *tx, id, user.user_id, Role::Admin).await?;
+
+    // Lock the invite so a concurrent join can't push uses_count past the new limit
+    let uses_count = sqlx::query_scalar::<_, i32>(
+        "SELECT uses_count FROM invites WHERE stream_id = $1 AND code = $2 FOR UPDATE",
+    )
+    .bind(id)
+    .bind(&code)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if request.max_uses.is_some_and(|max_uses| max_uses < uses_count) {
+        return Err(AppError::Validation(format!(
+            "max_uses cannot be lower than the current uses_count ({})",
+            uses_count
+        )));
+    }
+
+    // Role and code are fixed once the invite exists
+    let invite = sqlx::query_as::<_, Invite>(
+        "UPDATE invites
+         SET expires_at = COALESCE($3, expires_at),
+             max_uses = COALESCE($4, max_uses)
+         WHERE stream_id = $1 AND code = $2
+         RETURNING id, code, role, expires_at, max_uses, uses_count, email, email_delivered, created_at",
+    )
+    .bind(id)
+    .bind(&code)
+    .bind(request.expires_at)
+    .bind(request.max_uses)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(invite.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{id}/invites/{code}",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("code" = String, Path, description = "Invite code")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Invite revoked"),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream or invite not found", body = ErrorBody),
+    )
+)]
+async fn revoke_invite(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, code)): Path<(Uuid, String)>,
+) -> Result<StatusCode, AppError> {
+    require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    // Keep the original timestamp when revoking an already-revoked invite
+    let result = sqlx::query(
+        "UPDATE invites SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE stream_id = $1 AND code = $2",
+    )
+    .bind(id)
+    .bind(&code)
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ListVideosQuery {
+    /// `newest` (default), `oldest` or `most_liked`.
+    sort: Option<String>,
+    /// Only videos in this processing status, e.g. `ready`.
+    status: Option<String>,
+    /// Page size, 1-100 (default 20).
+    limit: Option<i64>,
+    /// `next_cursor` from the previous page.
+    cursor: Option<String>,
+}
+
+enum VideoSort {
+    Newest,
+    Oldest,
+    MostLiked,
+}
+
+impl VideoSort {
+    fn parse(sort: Option<&str>) -> Option<VideoSort> {
+        match sort {
+            None | Some("newest") => Some(VideoSort::Newest),
+            Some("oldest") => Some(VideoSort::Oldest),
+            Some("most_liked") => Some(VideoSort::MostLiked),
+            Some(_) => None,
+        }
+    }
+}
+
+// `newest`/`oldest` keyset position. Pinned videos sort ahead of the rest, so
+// their cursors carry a `p` prefix; unpinned ones are plain timestamps.
+struct CreatedKey {
+    pinned: bool,
+    created_at_nanos: i128,
+}
+
+impl fmt::Display for CreatedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if self.pinned { "p" } else { "" };
+        write!(f, "{}{}", prefix, self.created_at_nanos)
+    }
+}
+
+impl FromStr for CreatedKey {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let (pinned, nanos) = match key.strip_prefix('p') {
+            Some(nanos) => (true, nanos),
+            None => (false, key),
+        };
+        Ok(CreatedKey {
+            pinned,
+            created_at_nanos: nanos.parse()?,
+        })
+    }
+}
+
+// processing_index range covered by each `status` filter
+fn status_range(status: &str) -> Option<(i32, i32)> {
+    match status {
+        "queued" => Some((processing::QUEUED, processing::QUEUED)),
+        "processing" => Some((1, processing::READY - 1)),
+        "ready" => Some((processing::READY, processing::READY)),
+        "duplicate" => Some((processing::DUPLICATE, processing::DUPLICATE)),
+        "rejected" => Some((processing::REJECTED, processing::REJECTED)),
+        _ => None,
+    }
+}
+
+impl Validate for ListVideosQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if VideoSort::parse(self.sort.as_deref()).is_none() {
+            return Err(AppError::Validation("sort must be newest, oldest or most_liked".to_string()));
+        }
+        if self.status.as_deref().is_some_and(|status| status_range(status).is_none()) {
+            return Err(AppError::Validation(
+                "status must be queued, processing, ready, duplicate or rejected".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct VideoSummary {
+    id: Uuid,
+    title: String,
+    duration_seconds: Option<i32>,
+    processing_index: i32,
+    #[sqlx(skip)]
+    status: &'static str,
+    like_count: i64,
+    uploader_id: Uuid,
+    uploader_name: String,
+    #[serde(skip)]
+    thumbnail_path: Option<String>,
+    #[sqlx(skip)]
+    thumbnail_url: Option<String>,
+    /// Set while the video is pinned to the top of the stream.
+    #[serde(with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    pinned_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/videos",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ListVideosQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Videos in the stream", body = VideoPage),
+        (status = 400, description = "Invalid cursor", body = ErrorBody),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Not a member", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 422, description = "Invalid sort or status", body = ErrorBody),
+    )
+)]
+async fn list_videos(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<ListVideosQuery>,
+) -> Result<Json<Page<VideoSummary>>, AppError> {
+    // Both were checked by `validate`
+    let sort = VideoSort::parse(query.sort.as_deref()).unwrap_or(VideoSort::Newest);
+    let status_range = query.status.as_deref().and_then(status_range);
+
+    require_stream_role(&pool, id, user.user_id, Role::Viewer).await?;
+
+    let limit = pagination::page_limit(query.limit);
+    // `most_liked` pages on `(like_count, id)` rather than a timestamp
+    let (cursor_pinned, cursor_time, cursor_likes, cursor_id) = match (&sort, query.cursor.as_deref()) {
+        (_, None) => (false, None, None, None),
+        (VideoSort::MostLiked, Some(cursor)) => {
+            let (like_count, video_id) = pagination::decode_key_cursor::<i64>(cursor)?;
+            (false, None, Some(like_count), Some(video_id))
+        }
+        (_, Some(cursor)) => {
+            let (key, video_id) = pagination::decode_key_cursor::<CreatedKey>(cursor)?;
+            let created_at = OffsetDateTime::from_unix_timestamp_nanos(key.created_at_nanos)
+                .map_err(|_| AppError::BadRequest("Invalid cursor".to_string()))?;
+            (key.pinned, Some(created_at), None, Some(video_id))
+        }
+    };
+
+    // Pinned videos come first except when ranking by likes
+    let (order_by, after_cursor) = match sort {
+        VideoSort::Newest => (
+            "v.pinned_at IS NOT NULL DESC, v.created_at DESC, v.id DESC",
+            "(v.pinned_at IS NOT NULL, v.created_at, v.id) < ($8, $4, $6)",
+        ),
+        VideoSort::Oldest => (
+            "v.pinned_at IS NULL ASC, v.created_at ASC, v.id ASC",
+            "(v.pinned_at IS NULL, v.created_at, v.id) > (NOT $8, $4, $6)",
+        ),
+        VideoSort::MostLiked => ("lc.like_count DESC, v.id DESC", "(lc.like_count, v.id) < ($5, $6)"),
+    };
+
+    // Like counts come from one lateral aggregate per row, not a query per video
+    let mut videos = sqlx::query_as::<_, VideoSummary>(&format!(
+        "SELECT v.id, v.title, v.duration_seconds, v.processing_index, lc.like_count,
+                v.uploader_id, u.display_name AS uploader_name, v.thumbnail_path, v.pinned_at, v.created_at
+         FROM videos v
+         JOIN users u ON u.id = v.uploader_id
+         LEFT JOIN LATERAL (
+             SELECT COUNT(*) AS like_count FROM video_reactions WHERE video_id = v.id AND reaction = 'like'
+         ) lc ON TRUE
+         WHERE v.stream_id = $1
+           AND v.deleted_at IS NULL
+           AND ($2::int IS NULL OR v.processing_index BETWEEN $2 AND $3)
+           AND ($6::uuid IS NULL OR {})
+         ORDER BY {}
+         LIMIT $7",
+        after_cursor, order_by
+    ))
+    .bind(id)
+    .bind(status_range.map(|(min, _)| min))
+    .bind(status_range.map(|(_, max)| max))
+    .bind(cursor_time)
+    .bind(cursor_likes)
+    .bind(cursor_id)
+    .bind(limit + 1)
+    .bind(cursor_pinned)
+    .fetch_all(&pool)
+    .await?;
+
+    let next_cursor = if videos.len() as i64 > limit {
+        videos.truncate(limit as usize);
+        videos.last().map(|video| match sort {
+            VideoSort::MostLiked => pagination::encode_key_cursor(video.like_count, video.id),
+            _ => {
+                let key = CreatedKey {
+                    pinned: video.pinned_at.is_some(),
+                    created_at_nanos: video.created_at.unix_timestamp_nanos(),
+                };
+                pagination::encode_key_cursor(key, video.id)
+            }
+        })
+    } else {
+        None
+    };
+
+    for video in &mut videos {
+        video.status = processing::status_name(video.processing_index);
+        if let Some(path) = &video.thumbnail_path {
+            video.thumbnail_url = Some(signing::storage_get(path, Purpose::Stream).await?.url);
+        }
+    }
+
+    Ok(Json(Page {
+        items: videos,
+        next_cursor,
+    }))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct UploadVideoRequest {
+    title: String,
+    description: Option<String>,
+    content_type: String,
+    size_bytes: i64,
+    /// Hex SHA-256 of the file. If the stream already has a video with this
+    /// hash, that video is returned instead of registering a new one.
+    content_hash: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct CreatedVideo {
+    id: Uuid,
+    processing_index: i32,
+    status: &'static str,
+    #[serde(flatten)]
+    #[schema(inline)]
+    upload: videos::UploadUrlResponse,
+}
+
+/// Returned instead of [`CreatedVideo`] when the stream already has the file.
+#[derive(Serialize, ToSchema)]
+struct ExistingVideo {
+    id: Uuid,
+    title: String,
+    processing_index: i32,
+    status: &'static str,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// A stream's storage use against its quota.
+#[derive(sqlx::FromRow)]
+pub(crate) struct StreamUsage {
+    pub(crate) used_bytes: i64,
+    pub(crate) quota_bytes: i64,
+}
+
+impl StreamUsage {
+    /// Reject with `413` if adding `extra_bytes` would go over the quota.
+    pub(crate) fn check(&self, extra_bytes: i64) -> Result<(), AppError> {
+        if self.used_bytes + extra_bytes > self.quota_bytes {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Upload would exceed the stream's storage quota ({} of {} bytes used)",
+                self.used_bytes, self.quota_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Bytes used by the stream's videos and its quota. Locks the stream row
+/// until the transaction ends so concurrent uploads can't both squeeze under
+/// the quota.
+pub(crate) async fn lock_stream_usage(executor: impl PgExecutor<'_>, stream_id: Uuid) -> Result<StreamUsage, AppError> {
+    let usage = sqlx::query_as::<_, StreamUsage>(
+        "SELECT COALESCE(SUM(v.size_bytes), 0)::bigint AS used_bytes,
+                COALESCE(s.storage_quota_bytes, $2) AS quota_bytes
+         FROM (SELECT id, storage_quota_bytes FROM streams WHERE id = $1 FOR UPDATE) s
+         LEFT JOIN videos v ON v.stream_id = s.id
+         GROUP BY s.storage_quota_bytes",
+    )
+    .bind(stream_id)
+    .bind(config::get().stream_quota_bytes)
+    .fetch_one(executor)
+    .await?;
+    Ok(usage)
+}
+
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+struct StreamStorage {
+    stream_id: Uuid,
+    video_count: i64,
+    used_bytes: i64,
+    quota_bytes: i64,
+    /// Whether the quota was set for this stream rather than the server default.
+    custom_quota: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{id}/storage",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Storage used against the stream's quota", body = StreamStorage),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Only the owner or a global admin can view storage", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+    )
+)]
+async fn get_stream_storage(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StreamStorage>, AppError> {
+    if !user.is_admin {
+        let access = require_stream_role(&pool, id, user.user_id, Role::Viewer).await?;
+        if !access.is_owner {
+            return Err(AppError::Forbidden);
+        }
+    }
+
+    // Soft-deleted videos still count until they're purged
+    let storage = sqlx::query_as::<_, StreamStorage>(
+        "SELECT s.id AS stream_id,
+                COUNT(v.id) AS video_count,
+                COALESCE(SUM(v.size_bytes), 0)::bigint AS used_bytes,
+                COALESCE(s.storage_quota_bytes, $2) AS quota_bytes,
+                s.storage_quota_bytes IS NOT NULL AS custom_quota
+         FROM streams s
+         LEFT JOIN videos v ON v.stream_id = s.id
+         WHERE s.id = $1
+         GROUP BY s.id",
+    )
+    .bind(id)
+    .bind(config::get().stream_quota_bytes)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(storage))
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{id}/videos",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id")),
+    request_body = UploadVideoRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The stream already has a video with this content_hash", body = ExistingVideo),
+        (status = 201, description = "Video registered; upload to the presigned URL", body = CreatedVideo),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires creator role", body = ErrorBody),
+        (status = 404, description = "Stream not found", body = ErrorBody),
+        (status = 413, description = "Stream storage quota exceeded", body = ErrorBody),
+        (status = 422, description = "Invalid title, description, content type, size or content hash", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    )
+)]
+async fn upload_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UploadVideoRequest>,
+) -> Result<Response, AppError> {
+    let title = videos::validate_title(&request.title)?;
+    if let Some(description) = &request.description {
+        videos::validate_description(description)?;
+    }
+    let extension = videos::upload_extension(&request.content_type)?;
+    if request.size_bytes < 1 {
+        return Err(AppError::Validation("size_bytes must be positive".to_string()));
+    }
+    let content_hash = request.content_hash.as_deref().map(videos::normalize_content_hash).transpose()?;
+
+    let mut tx = pool.begin().await?;
+    require_stream_role(&mut *tx, id, user.user_id, Role::Creator).await?;
+
+    // Taken before the hash lookup so concurrent uploads of one file can't both miss
+    let usage = lock_stream_usage(&mut *tx, id).await?;
+    if let Some(hash) = &content_hash
+        && let Some(existing_id) = videos::find_by_content_hash(&mut *tx, id, hash, None).await?
+    {
+        let (title, processing_index, created_at) = sqlx::query_as::<_, (String, i32, OffsetDateTime)>(
+            "SELECT title, processing_index, created_at FROM videos WHERE id = $1",
+        )
+        .bind(existing_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        return Ok(Json(ExistingVideo {
+            id: existing_id,
+            title,
+            processing_index,
+            status: processing::status_name(processing_index),
+            created_at,
+        })
+        .into_response());
+    }
+    usage.check(request.size_bytes)?;
+
+    let video_id = Uuid::new_v4();
+    let key = format!("raw-uploads/{}.{}", video_id, extension);
+
+    sqlx::query(
+        "INSERT INTO videos
+             (id, stream_id, uploader_id, title, description, size_bytes, storage_path, processing_index, content_hash)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(video_id)
+    .bind(id)
+    .bind(user.user_id)
+    .bind(title)
+    .bind(&request.description)
+    .bind(request.size_bytes)
+    .bind(&key)
+    .bind(processing::QUEUED)
+    .bind(&content_hash)
+    .execute(&mut *tx)
+    .await?;
+
+    // Workers ignore the job until the upload is confirmed and it moves to 'queued'
+    sqlx::query(
+        "INSERT INTO processing_jobs (video_id, stage, status) VALUES ($1, $2, 'awaiting_upload')",
+    )
+    .bind(video_id)
+    .bind(processing::STAGES[0])
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let upload = signing::storage_put(&key, Purpose::Upload).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedVideo {
+            id: video_id,
+            processing_index: processing::QUEUED,
+            status: processing::status_name(processing::QUEUED),
+            upload: videos::UploadUrlResponse {
+                upload_url: upload.url,
+                expires_at: upload.expires_at,
+                headers: HashMap::from([("Content-Type", request.content_type)]),
+            },
+        }),
+    )
+        .into_response())
+}
+
+/// Most videos a stream can have pinned at once.
+const MAX_PINNED_VIDEOS: i64 = 5;
+
+#[derive(Serialize, ToSchema)]
+struct PinnedVideo {
+    video_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pinned_at: OffsetDateTime,
+}
+
+#[utoipa::path(
+    put,
+    path = "/streams/{id}/videos/{video_id}/pin",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("video_id" = Uuid, Path, description = "Video id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Video is pinned; pinning it again keeps the original time", body = PinnedVideo),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream not found or video not in it", body = ErrorBody),
+        (status = 409, description = "The stream already has the maximum number of pinned videos", body = ErrorBody),
+    )
+)]
+async fn pin_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, video_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<PinnedVideo>, AppError> {
+    let mut tx = pool.begin().await?;
+    require_stream_role(&mut *tx, id, user.user_id, Role::Admin).await?;
+
+    // Serializes concurrent pins so the limit holds
+    sqlx::query("SELECT id FROM streams WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let pinned_at = sqlx::query_scalar::<_, Option<OffsetDateTime>>(
+        "SELECT pinned_at FROM videos WHERE id = $1 AND stream_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(video_id)
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if let Some(pinned_at) = pinned_at {
+        return Ok(Json(PinnedVideo { video_id, pinned_at }));
+    }
+
+    let pinned_count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM videos WHERE stream_id = $1 AND pinned_at IS NOT NULL AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if pinned_count >= MAX_PINNED_VIDEOS {
+        return Err(AppError::Conflict(format!(
+            "A stream can have at most {} pinned videos",
+            MAX_PINNED_VIDEOS
+        )));
+    }
+
+    let pinned_at = sqlx::query_scalar::<_, OffsetDateTime>(
+        "UPDATE videos SET pinned_at = NOW() WHERE id = $1 RETURNING pinned_at",
+    )
+    .bind(video_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Json(PinnedVideo { video_id, pinned_at }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{id}/videos/{video_id}/pin",
+    tag = "streams",
+    params(("id" = Uuid, Path, description = "Stream id"), ("video_id" = Uuid, Path, description = "Video id")),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Video is no longer pinned"),
+        (status = 401, description = "Missing or invalid app token", body = ErrorBody),
+        (status = 403, description = "Requires admin role", body = ErrorBody),
+        (status = 404, description = "Stream not found or video not in it", body = ErrorBody),
+    )
+)]
+async fn unpin_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, video_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_stream_role(&pool, id, user.user_id, Role::Admin).await?;
+
+    // Unpinning a video that isn't pinned is a no-op
+    let result = sqlx::query(
+        "UPDATE videos SET pinned_at = NULL WHERE id = $1 AND stream_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(video_id)
+    .bind(id)
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Names for the pages these routes return, so each gets its own schema in the spec
+type StreamPage = Page<StreamSummary>;
+type MemberPage = Page<Member>;
+type InvitePage = Page<InviteStatus>;
+type VideoPage = Page<VideoSummary>;
+
+impl<'s> ToSchema<'s> for StreamPage {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        ("StreamPage", pagination::page_schema::<StreamSummary>())
+    }
+}
+
+impl<'s> ToSchema<'s> for MemberPage {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        ("MemberPage", pagination::page_schema::<Member>())
+    }
+}
+
+impl<'s> ToSchema<'s> for InvitePage {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        ("InvitePage", pagination::page_schema::<InviteStatus>())
+    }
+}
+
+impl<'s> ToSchema<'s> for VideoPage {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        ("VideoPage", pagination::page_schema::<VideoSummary>())
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_streams,
+        create_stream,
+        get_stream,
+        get_my_role,
+        get_stream_storage,
+        update_stream,
+        delete_stream,
+        transfer_stream,
+        list_members,
+        update_member,
+        remove_member,
+        bulk_add_members,
+        list_invites,
+        create_invite,
+        get_invite,
+        update_invite,
+        revoke_invite,
+        list_videos,
+        upload_video,
+        pin_video,
+        unpin_video,
+    ),
+    components(schemas(
+        Role,
+        StreamSummary,
+        StreamPage,
+        CreateStreamRequest,
+        UpdateStreamRequest,
+        TransferStreamRequest,
+        StreamRecord,
+        StreamOwner,
+        StreamDetails,
+        MyRole,
+        StreamStorage,
+        Member,
+        MemberPage,
+        UpdateMemberRequest,
+        Membership,
+        BulkMembersRequest,
+        BulkMemberStatus,
+        BulkMemberResult,
+        BulkMembersResponse,
+        CreateInviteRequest,
+        Invite,
+        InviteStatus,
+        InvitePage,
+        CreatedInvite,
+        InviteUse,
+        InviteDetails,
+        UpdateInviteRequest,
+        VideoSummary,
+        VideoPage,
+        UploadVideoRequest,
+        CreatedVideo,
+        ExistingVideo,
+        PinnedVideo,
+    ))
+)]
+pub(crate) struct StreamsApi;
+
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/streams", get(list_streams))
@@ -125,7 +2122,11 @@ pub fn routes() -> Router<PgPool> {
         .route("/streams/:id", get(get_stream))
         .route("/streams/:id", put(update_stream))
         .route("/streams/:id", delete(delete_stream))
+        .route("/streams/:id/transfer", post(transfer_stream))
+        .route("/streams/:id/my-role", get(get_my_role))
+        .route("/streams/:id/storage", get(get_stream_storage))
         .route("/streams/:id/members", get(list_members))
+        .route("/streams/:id/members/bulk", post(bulk_add_members))
         .route("/streams/:id/members/:user_id", put(update_member))
         .route("/streams/:id/members/:user_id", delete(remove_member))
         .route("/streams/:id/invites", get(list_invites))
@@ -134,5 +2135,140 @@ pub fn routes() -> Router<PgPool> {
         .route("/streams/:id/invites/:code", put(update_invite))
         .route("/streams/:id/invites/:code", delete(revoke_invite))
         .route("/streams/:id/videos", get(list_videos))
-        .route("/streams/:id/videos", post(upload_video))
+        .route(
+            "/streams/:id/videos",
+            post(upload_video).layer(middleware::from_fn_with_state(Group::Upload, rate_limit::enforce)),
+        )
+        .route("/streams/:id/videos/:video_id/pin", put(pin_video))
+        .route("/streams/:id/videos/:video_id/pin", delete(unpin_video))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_member, db_test, stream, user};
+
+    // A stream owned by `owner` with `member` in it as `role`
+    async fn stream_with_member(pool: &PgPool, owner: Uuid, member: Uuid, role: Role) -> Uuid {
+        let stream_id = stream(pool, owner).await;
+        add_member(pool, stream_id, member, role).await;
+        stream_id
+    }
+
+    async fn role_of(pool: &PgPool, stream_id: Uuid, user_id: Uuid) -> String {
+        sqlx::query_scalar::<_, String>("SELECT role FROM stream_members WHERE stream_id = $1 AND user_id = $2")
+            .bind(stream_id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn transfer(
+        pool: &PgPool,
+        owner: Uuid,
+        stream_id: Uuid,
+        new_owner: Uuid,
+        demote_previous_owner: bool,
+    ) -> Result<StreamRecord, AppError> {
+        let user = AuthUser {
+            user_id: owner,
+            email: "owner@example.com".to_string(),
+            is_admin: false,
+        };
+        let request = TransferStreamRequest {
+            new_owner_user_id: new_owner,
+            demote_previous_owner,
+        };
+        transfer_stream(State(pool.clone()), user, Path(stream_id), Json(request))
+            .await
+            .map(|Json(stream)| stream)
+    }
+
+    // `PUT /streams/{id}` from a global admin who isn't in the stream, setting a quota and maybe a name
+    async fn update_as_outside_admin(pool: &PgPool, stream_id: Uuid, name: Option<&str>) -> Result<(), AppError> {
+        let admin = AuthUser {
+            user_id: user(pool, "admin").await,
+            email: "admin@example.com".to_string(),
+            is_admin: true,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(axum::http::header::IF_MATCH, "*".parse().unwrap());
+        let request = UpdateStreamRequest {
+            name: name.map(str::to_string),
+            description: None,
+            settings: None,
+            storage_quota_bytes: Some(1024),
+        };
+        update_stream(State(pool.clone()), admin, Path(stream_id), headers, Json(request)).await.map(|_| ())
+    }
+
+    db_test! {
+        async fn global_admin_sets_quota_without_membership(pool: PgPool) {
+            let (owner, member) = (user(&pool, "owner").await, user(&pool, "member").await);
+            let stream_id = stream_with_member(&pool, owner, member, Role::Viewer).await;
+
+            assert!(update_as_outside_admin(&pool, stream_id, None).await.is_ok());
+        }
+
+        async fn global_admin_needs_creator_role_for_other_fields(pool: PgPool) {
+            let (owner, member) = (user(&pool, "owner").await, user(&pool, "member").await);
+            let stream_id = stream_with_member(&pool, owner, member, Role::Viewer).await;
+
+            let result = update_as_outside_admin(&pool, stream_id, Some("Renamed")).await;
+            assert!(matches!(result, Err(AppError::Forbidden)));
+            let (name, quota) = sqlx::query_as::<_, (String, Option<i64>)>(
+                "SELECT name, storage_quota_bytes FROM streams WHERE id = $1",
+            )
+            .bind(stream_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!((name.as_str(), quota), ("Stream", None));
+        }
+
+        async fn transfer_to_admin_keeps_admin_role(pool: PgPool) {
+            let (owner, admin) = (user(&pool, "owner").await, user(&pool, "admin").await);
+            let stream_id = stream_with_member(&pool, owner, admin, Role::Admin).await;
+
+            let stream = transfer(&pool, owner, stream_id, admin, false).await.unwrap();
+            assert_eq!(stream.owner_id, admin);
+            assert_eq!(role_of(&pool, stream_id, admin).await, "admin");
+            assert_eq!(role_of(&pool, stream_id, owner).await, "creator");
+        }
+
+        async fn transfer_to_viewer_raises_them_to_creator(pool: PgPool) {
+            let (owner, viewer) = (user(&pool, "owner").await, user(&pool, "viewer").await);
+            let stream_id = stream_with_member(&pool, owner, viewer, Role::Viewer).await;
+
+            let stream = transfer(&pool, owner, stream_id, viewer, false).await.unwrap();
+            assert_eq!(stream.owner_id, viewer);
+            assert_eq!(role_of(&pool, stream_id, viewer).await, "creator");
+        }
+
+        async fn transfer_can_demote_previous_owner(pool: PgPool) {
+            let (owner, creator) = (user(&pool, "owner").await, user(&pool, "creator").await);
+            let stream_id = stream_with_member(&pool, owner, creator, Role::Creator).await;
+
+            transfer(&pool, owner, stream_id, creator, true).await.unwrap();
+            assert_eq!(role_of(&pool, stream_id, creator).await, "creator");
+            assert_eq!(role_of(&pool, stream_id, owner).await, "viewer");
+        }
+
+        async fn transfer_to_non_member_is_rejected(pool: PgPool) {
+            let (owner, member, outsider) =
+                (user(&pool, "owner").await, user(&pool, "member").await, user(&pool, "outsider").await);
+            let stream_id = stream_with_member(&pool, owner, member, Role::Viewer).await;
+
+            let result = transfer(&pool, owner, stream_id, outsider, true).await;
+            assert!(matches!(result, Err(AppError::Validation(_))));
+            let owner_id = sqlx::query_scalar::<_, Uuid>("SELECT owner_id FROM streams WHERE id = $1")
+                .bind(stream_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(owner_id, owner);
+            assert_eq!(role_of(&pool, stream_id, owner).await, "creator");
+        }
+    }
 }
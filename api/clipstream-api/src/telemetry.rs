@@ -0,0 +1,124 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use sqlx::PgPool;
+use std::{sync::OnceLock, time::Instant};
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+static REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+// Inbound request ids longer than this are replaced rather than echoed
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+// Latency buckets in seconds, from fast lookups up to slow exports
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the global log subscriber. Verbosity comes from `RUST_LOG`
+/// (default `info`).
+pub fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+/// Install the global Prometheus recorder that `/metrics` renders from.
+pub fn init() -> Result<(), metrics_exporter_prometheus::BuildError> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full("http_request_duration_seconds".to_string()),
+            &LATENCY_BUCKETS,
+        )?
+        .install_recorder()?;
+    let _ = PROMETHEUS.set(handle);
+    Ok(())
+}
+
+/// Connection pool usage as `(size, idle, in_use)`.
+pub fn pool_stats(pool: &PgPool) -> (u32, usize, usize) {
+    let size = pool.size();
+    let idle = pool.num_idle();
+    (size, idle, (size as usize).saturating_sub(idle))
+}
+
+/// Run each request inside a span tagged with its request id, so every log
+/// line a handler emits carries it. An inbound `X-Request-Id` is honored,
+/// otherwise a fresh UUID is assigned; either way it's echoed back.
+pub async fn trace_requests(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    async move {
+        let start = Instant::now();
+        let mut response = next.run(request).await;
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = start.elapsed().as_millis() as u64,
+            "request completed"
+        );
+
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID.clone(), value);
+        }
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// Record count, latency and in-flight gauges per method/route/status. The
+/// route template is used instead of the raw path to keep label cardinality
+/// bounded.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let in_flight = metrics::gauge!("http_requests_in_flight");
+    in_flight.increment(1.0);
+    let start = Instant::now();
+    let response = next.run(request).await;
+    in_flight.decrement(1.0);
+
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels).record(start.elapsed().as_secs_f64());
+
+    response
+}
+
+// Prometheus scrape endpoint; pool gauges are sampled at scrape time
+pub async fn render(State(pool): State<PgPool>) -> Response {
+    let (size, idle, in_use) = pool_stats(&pool);
+    metrics::gauge!("db_pool_size").set(size as f64);
+    metrics::gauge!("db_pool_idle").set(idle as f64);
+    metrics::gauge!("db_pool_in_use").set(in_use as f64);
+
+    let body = PROMETHEUS.get().map(PrometheusHandle::render).unwrap_or_default();
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
@@ -0,0 +1,53 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::streams::Role;
+
+/// Declares `async fn name(pool: PgPool)` tests as `#[sqlx::test]`s, each
+/// run against a fresh database with every migration applied. They're
+/// ignored by a plain `cargo test`, which has no database; CI runs them with
+/// `--include-ignored` against Postgres with pgvector.
+macro_rules! db_test {
+    ($(async fn $name:ident($pool:ident: PgPool) $body:block)*) => {
+        $(
+            #[sqlx::test(migrator = "crate::MIGRATOR")]
+            #[ignore = "needs DATABASE_URL pointing at Postgres with pgvector"]
+            async fn $name($pool: sqlx::PgPool) $body
+        )*
+    };
+}
+
+pub(crate) use db_test;
+
+/// A user whose Google subject, email local part and display name are all `name`.
+pub(crate) async fn user(executor: impl PgExecutor<'_>, name: &str) -> Uuid {
+    sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO users (google_sub, email, display_name) VALUES ($1, $1 || '@example.com', $1) RETURNING id",
+    )
+    .bind(name)
+    .fetch_one(executor)
+    .await
+    .unwrap()
+}
+
+/// A stream owned by `owner`, who joins it as creator, as in `create_stream`.
+pub(crate) async fn stream(pool: &PgPool, owner: Uuid) -> Uuid {
+    let stream_id =
+        sqlx::query_scalar::<_, Uuid>("INSERT INTO streams (name, owner_id) VALUES ('Stream', $1) RETURNING id")
+            .bind(owner)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    add_member(pool, stream_id, owner, Role::Creator).await;
+    stream_id
+}
+
+pub(crate) async fn add_member(pool: &PgPool, stream_id: Uuid, user_id: Uuid, role: Role) {
+    sqlx::query("INSERT INTO stream_members (stream_id, user_id, role) VALUES ($1, $2, $3)")
+        .bind(stream_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .execute(pool)
+        .await
+        .unwrap();
+}
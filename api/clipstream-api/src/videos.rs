@@ -1,154 +1,2307 @@
 use axum::{
-    extract::Path,
-    response::Json,
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{delete, get, post, put},
     Router,
 };
-use serde::Serialize;
-use sqlx::PgPool;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgExecutor, PgPool};
+use std::{collections::{BTreeMap, HashMap}, convert::Infallible, time::Duration};
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    codes,
+    conditional::{self, Precondition},
+    config,
+    error::AppError,
+    files,
+    processing,
+    rate_limit::{self, Group},
+    signing::{self, Purpose},
+    sniff,
+    storage,
+    pagination::{self, Page, PageQuery},
+    query::{Validate, ValidQuery},
+    streams::{self, Role},
+};
+
+const SHARE_CODE_LEN: usize = 10;
+const MAX_TITLE_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 5000;
+const DEFAULT_SIMILAR_LIMIT: i64 = 10;
+const MAX_SIMILAR_LIMIT: i64 = 50;
+const MAX_BATCH_IDS: usize = 100;
+const DELETED_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DELETED_SWEEP_BATCH: i64 = 100;
+const MAX_TRANSCRIPT_MATCHES: i64 = 200;
+const MAX_SHARE_VIEW_TIMESTAMPS: i64 = 1000;
+
+// Reactions a member can leave on a video; `like` also drives like counts
+const REACTION_TYPES: [&str; 6] = ["like", "love", "fire", "laugh", "wow", "sad"];
+
+// Accepted upload content types and the file extension stored for each
+pub(crate) const ALLOWED_UPLOAD_TYPES: [(&str, &str); 3] = [
+    ("video/mp4", "mp4"),
+    ("video/quicktime", "mov"),
+    ("video/webm", "webm"),
+];
+
+/// Every object-storage key belonging to a video: the original upload, its
+/// thumbnail, and the derived artifacts from each processing stage. Keys
+/// ending in `/` are prefixes covering many objects.
+pub(crate) fn storage_keys(id: Uuid, storage_path: Option<String>, thumbnail_path: Option<String>) -> Vec<String> {
+    let mut keys: Vec<String> = storage_path.into_iter().collect();
+    keys.extend(derived_storage_keys(id, thumbnail_path));
+    keys
+}
+
+/// The keys from [`storage_keys`] that processing can regenerate, i.e.
+/// everything except the original upload.
+pub(crate) fn derived_storage_keys(id: Uuid, thumbnail_path: Option<String>) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    if let Some(path) = thumbnail_path {
+        keys.extend(ThumbnailSize::ALL.iter().map(|size| size.key(&path)));
+        keys.push(path);
+    }
+    keys.push(format!("processed-videos/{}/", id));
+    keys.push(format!("transcripts/{}.json", id));
+    keys.push(format!("embeddings/{}.json", id));
+    keys.push(storyboard_key(id));
+    keys
+}
+
+/// Resized copies of a video's thumbnail. Processing may generate any of
+/// them alongside the full-size thumbnail.
+#[derive(Clone, Copy)]
+pub(crate) enum ThumbnailSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ThumbnailSize {
+    pub(crate) const ALL: [ThumbnailSize; 3] = [ThumbnailSize::Small, ThumbnailSize::Medium, ThumbnailSize::Large];
+
+    pub(crate) fn parse(size: &str) -> Option<ThumbnailSize> {
+        ThumbnailSize::ALL.into_iter().find(|known| known.as_str() == size)
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ThumbnailSize::Small => "small",
+            ThumbnailSize::Medium => "medium",
+            ThumbnailSize::Large => "large",
+        }
+    }
+
+    /// Width in pixels; heights keep the video's aspect ratio.
+    pub(crate) fn width(self) -> u32 {
+        match self {
+            ThumbnailSize::Small => 320,
+            ThumbnailSize::Medium => 640,
+            ThumbnailSize::Large => 1280,
+        }
+    }
+
+    /// The smallest size at least `width` pixels wide, or the largest.
+    pub(crate) fn for_width(width: u32) -> ThumbnailSize {
+        ThumbnailSize::ALL
+            .into_iter()
+            .find(|size| size.width() >= width)
+            .unwrap_or(ThumbnailSize::Large)
+    }
+
+    /// Storage key of this size, next to the full-size thumbnail:
+    /// `thumbnails/{id}.jpg` becomes `thumbnails/{id}-small.jpg`.
+    pub(crate) fn key(self, thumbnail_path: &str) -> String {
+        match thumbnail_path.rsplit_once('.') {
+            Some((stem, extension)) if !extension.contains('/') => {
+                format!("{}-{}.{}", stem, self.as_str(), extension)
+            }
+            _ => format!("{}-{}", thumbnail_path, self.as_str()),
+        }
+    }
+}
+
+/// Where the transcoding stage uploads a video's storyboard sprite sheet.
+pub(crate) fn storyboard_key(id: Uuid) -> String {
+    format!("storyboards/{}.jpg", id)
+}
+
+/// Delete a video's objects from storage once its row is gone. Anything that
+/// fails here is queued for a later retry rather than failing the request.
+pub(crate) async fn purge_video_objects(
+    pool: &PgPool,
+    id: Uuid,
+    storage_path: Option<String>,
+    thumbnail_path: Option<String>,
+) {
+    delete_video_objects(pool, id, storage_keys(id, storage_path, thumbnail_path)).await;
+}
+
+/// Delete some of a video's objects, queueing any failures for a later retry.
+pub(crate) async fn delete_video_objects(pool: &PgPool, id: Uuid, keys: Vec<String>) {
+    let failed = storage::delete_objects(keys).await;
+    if !failed.is_empty()
+        && let Err(err) = sqlx::query("INSERT INTO storage_deletions (object_key) SELECT UNNEST($1::text[])")
+            .bind(&failed)
+            .execute(pool)
+            .await
+    {
+        tracing::error!(video_id = %id, error = %err, "Failed to queue storage deletions");
+    }
+}
+
+/// Ensure the caller may manage a video: its uploader (while still a member)
+/// or an admin/owner of its stream. Returns the video's stream id.
+/// Soft-deleted videos are treated as missing.
+pub(crate) async fn require_video_manager(
+    executor: impl PgExecutor<'_>,
+    video_id: Uuid,
+    user_id: Uuid,
+) -> Result<Uuid, AppError> {
+    check_video_manager(executor, video_id, user_id, false).await
+}
+
+// `include_deleted` lets restore and hard delete reach soft-deleted videos
+async fn check_video_manager(
+    executor: impl PgExecutor<'_>,
+    video_id: Uuid,
+    user_id: Uuid,
+    include_deleted: bool,
+) -> Result<Uuid, AppError> {
+    let (stream_id, is_uploader, is_owner, role) = sqlx::query_as::<_, (Uuid, bool, bool, Option<String>)>(
+        "SELECT v.stream_id, v.uploader_id = $2, s.owner_id = $2, m.role
+         FROM videos v
+         JOIN streams s ON s.id = v.stream_id
+         LEFT JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $2
+         WHERE v.id = $1 AND ($3 OR v.deleted_at IS NULL)",
+    )
+    .bind(video_id)
+    .bind(user_id)
+    .bind(include_deleted)
+    .fetch_one(executor)
+    .await?;
+
+    let role = role.as_deref().and_then(Role::parse);
+    let allowed = is_owner || role >= Some(Role::Admin) || (is_uploader && role.is_some());
+    if !allowed {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(stream_id)
+}
+
+async fn select_video<T>(executor: impl PgExecutor<'_>, id: Uuid, columns: &str, lock: &str) -> Result<T, AppError>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    let sql = format!("SELECT {} FROM videos WHERE id = $1 AND deleted_at IS NULL{}", columns, lock);
+    Ok(sqlx::query_as::<_, T>(&sql).bind(id).fetch_one(executor).await?)
+}
+
+/// Read `columns` (names or expressions over `videos`) for a single video.
+/// Soft-deleted videos are `404` like missing ones, so every route that takes
+/// a video id should look it up through here.
+pub(crate) async fn fetch_video<T>(executor: impl PgExecutor<'_>, id: Uuid, columns: &str) -> Result<T, AppError>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    select_video(executor, id, columns, "").await
+}
+
+/// [`fetch_video`], locking the row for the rest of the transaction.
+pub(crate) async fn lock_video<T>(executor: impl PgExecutor<'_>, id: Uuid, columns: &str) -> Result<T, AppError>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    select_video(executor, id, columns, " FOR UPDATE").await
+}
+
+/// The stream a (not soft-deleted) video belongs to.
+pub(crate) async fn video_stream_id(executor: impl PgExecutor<'_>, id: Uuid) -> Result<Uuid, AppError> {
+    let (stream_id,) = fetch_video::<(Uuid,)>(executor, id, "stream_id").await?;
+    Ok(stream_id)
+}
+
+#[derive(sqlx::FromRow)]
+struct VideoRow {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    description: Option<String>,
+    duration_seconds: Option<i32>,
+    size_bytes: Option<i64>,
+    processing_index: i32,
+    thumbnail_path: Option<String>,
+    uploader_id: Uuid,
+    uploader_name: String,
+    version: i32,
+    created_at: OffsetDateTime,
+    updated_at: OffsetDateTime,
+    duplicate_of: Option<Uuid>,
+}
 
 #[derive(Serialize)]
-struct VideoResponse {
-    message: String,
-    purpose: String,
+struct Uploader {
+    id: Uuid,
+    display_name: String,
 }
 
-async fn get_video(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get video {} endpoint", id),
-        purpose: "Get detailed video information including metadata, processing status, and access permissions".to_string(),
-    })
+#[derive(Serialize)]
+struct VideoDetails {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    description: Option<String>,
+    duration_seconds: Option<i32>,
+    size_bytes: Option<i64>,
+    processing_index: i32,
+    status: &'static str,
+    thumbnail_key: Option<String>,
+    uploader: Uploader,
+    /// The closest earlier video, for one flagged as a duplicate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicate_of: Option<Uuid>,
+    /// Send back in `If-Match` when updating.
+    version: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
 }
 
-async fn update_video(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Update video {} endpoint", id),
-        purpose: "Update video metadata like title, description, and other editable properties".to_string(),
-    })
+impl From<VideoRow> for VideoDetails {
+    fn from(row: VideoRow) -> Self {
+        VideoDetails {
+            id: row.id,
+            stream_id: row.stream_id,
+            title: row.title,
+            description: row.description,
+            duration_seconds: row.duration_seconds,
+            size_bytes: row.size_bytes,
+            processing_index: row.processing_index,
+            status: processing::status_name(row.processing_index),
+            thumbnail_key: row.thumbnail_path,
+            uploader: Uploader {
+                id: row.uploader_id,
+                display_name: row.uploader_name,
+            },
+            duplicate_of: row.duplicate_of,
+            version: row.version,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
 }
 
-async fn delete_video(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Delete video {} endpoint", id),
-        purpose: "Delete video file and all associated data from storage and database".to_string(),
-    })
+async fn get_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let video = sqlx::query_as::<_, VideoRow>(
+        "SELECT v.id, v.stream_id, v.title, v.description, v.duration_seconds, v.size_bytes,
+                v.processing_index, v.thumbnail_path, v.uploader_id,
+                u.display_name AS uploader_name, v.version, v.created_at, v.updated_at,
+                (SELECT d.original_video_id FROM duplicate_matches d
+                 WHERE d.video_id = v.id ORDER BY d.similarity DESC LIMIT 1) AS duplicate_of
+         FROM videos v
+         JOIN users u ON u.id = v.uploader_id
+         WHERE v.id = $1 AND v.deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    streams::require_stream_role(&pool, video.stream_id, user.user_id, Role::Viewer).await?;
+
+    let updated_at = video.updated_at;
+    Ok(conditional::respond(&headers, updated_at, Json(VideoDetails::from(video))))
 }
 
-async fn get_video_stream(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get video {} stream URLs endpoint", id),
-        purpose: "Get HLS/DASH streaming URLs for video playback (only available after processing complete)".to_string(),
-    })
+#[derive(Deserialize)]
+struct BatchRequest {
+    ids: Vec<Uuid>,
 }
 
-async fn get_upload_url(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get upload URL for video {} endpoint", id),
-        purpose: "Get presigned upload URL for large video files to upload directly to storage".to_string(),
-    })
+#[derive(sqlx::FromRow)]
+struct BatchRow {
+    #[sqlx(flatten)]
+    video: VideoRow,
+    member_role: Option<String>,
 }
 
-async fn get_processing_status(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get processing status for video {} endpoint", id),
-        purpose: "Get current processing stage, progress, and estimated completion time".to_string(),
-    })
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UnavailableReason {
+    NotFound,
+    Forbidden,
 }
 
-async fn get_duplicates(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get duplicates for video {} endpoint", id),
-        purpose: "Get duplicate detection results if video was flagged as duplicate (processing_index = -1)".to_string(),
-    })
+#[derive(Serialize)]
+struct UnavailableVideo {
+    id: Uuid,
+    reason: UnavailableReason,
 }
 
-async fn get_similar(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get similar videos for {} endpoint", id),
-        purpose: "Get AI-detected similar clips and related videos based on content analysis".to_string(),
-    })
+#[derive(Serialize)]
+struct BatchResponse {
+    videos: Vec<VideoDetails>,
+    unavailable: Vec<UnavailableVideo>,
 }
 
-async fn get_trimmed(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get trimmed clips for video {} endpoint", id),
-        purpose: "Get automatically generated trimmed clips with timeline data and highlights".to_string(),
-    })
+// Same shape as `get_video` for each id, in request order. Ids the caller
+// can't see are listed in `unavailable` instead of failing the batch.
+async fn get_videos_batch(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Json(req): Json<BatchRequest>,
+) -> Result<Json<BatchResponse>, AppError> {
+    let mut ids = Vec::with_capacity(req.ids.len());
+    for id in req.ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() || ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::Validation(format!(
+            "ids must contain between 1 and {} video ids",
+            MAX_BATCH_IDS
+        )));
+    }
+
+    let rows = sqlx::query_as::<_, BatchRow>(
+        "SELECT v.id, v.stream_id, v.title, v.description, v.duration_seconds, v.size_bytes,
+                v.processing_index, v.thumbnail_path, v.uploader_id,
+                u.display_name AS uploader_name, v.version, v.created_at, v.updated_at,
+                (SELECT d.original_video_id FROM duplicate_matches d
+                 WHERE d.video_id = v.id ORDER BY d.similarity DESC LIMIT 1) AS duplicate_of,
+                m.role AS member_role
+         FROM videos v
+         JOIN users u ON u.id = v.uploader_id
+         LEFT JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $2
+         WHERE v.id = ANY($1) AND v.deleted_at IS NULL",
+    )
+    .bind(&ids)
+    .bind(user.user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    // Any member role passes, matching `require_stream_role(.., Role::Viewer)`
+    let mut found: HashMap<Uuid, BatchRow> = rows.into_iter().map(|row| (row.video.id, row)).collect();
+    let mut response = BatchResponse {
+        videos: Vec::with_capacity(found.len()),
+        unavailable: Vec::new(),
+    };
+    for id in ids {
+        match found.remove(&id) {
+            Some(row) if row.member_role.as_deref().and_then(Role::parse).is_some() => {
+                response.videos.push(row.video.into());
+            }
+            Some(_) => response.unavailable.push(UnavailableVideo {
+                id,
+                reason: UnavailableReason::Forbidden,
+            }),
+            None => response.unavailable.push(UnavailableVideo {
+                id,
+                reason: UnavailableReason::NotFound,
+            }),
+        }
+    }
+
+    Ok(Json(response))
 }
 
-async fn get_pov(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get POV clips for video {} endpoint", id),
-        purpose: "Get different point-of-view clips detected from the same gameplay moment".to_string(),
-    })
+// Unknown fields (duration_seconds, size_bytes, ...) are ignored by serde
+#[derive(Deserialize)]
+struct UpdateVideoRequest {
+    title: Option<String>,
+    description: Option<String>,
 }
 
-async fn get_transcript(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get transcript for video {} endpoint", id),
-        purpose: "Get speech-to-text transcript data with timestamps for searchable content".to_string(),
-    })
+pub(crate) fn validate_title(title: &str) -> Result<&str, AppError> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LEN {
+        return Err(AppError::Validation(format!(
+            "title must be between 1 and {} characters",
+            MAX_TITLE_LEN
+        )));
+    }
+    Ok(title)
 }
 
-async fn get_embeddings(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get embeddings for video {} endpoint", id),
-        purpose: "Get AI-generated video embeddings for content-based similarity matching".to_string(),
-    })
+pub(crate) fn validate_description(description: &str) -> Result<(), AppError> {
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(AppError::Validation(format!(
+            "description must be at most {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    Ok(())
 }
 
-async fn get_timeline(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get timeline for video {} endpoint", id),
-        purpose: "Get timeline view data with trimmed clips, highlights, and navigation markers".to_string(),
-    })
+async fn update_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateVideoRequest>,
+) -> Result<Json<VideoDetails>, AppError> {
+    let precondition = Precondition::from_headers(&headers)?;
+    let title = request.title.as_deref().map(validate_title).transpose()?;
+    if let Some(description) = &request.description {
+        validate_description(description)?;
+    }
+
+    let mut tx = pool.begin().await?;
+    require_video_manager(&mut *tx, id, user.user_id).await?;
+
+    // Checked under the row lock so two edits from the same version can't both win
+    let (version,) = lock_video::<(i32,)>(&mut *tx, id, "version").await?;
+    precondition.check(version)?;
+
+    // Omitted fields keep their current values
+    let video = sqlx::query_as::<_, VideoRow>(
+        "WITH v AS (
+             UPDATE videos
+             SET title = COALESCE($2, title),
+                 description = COALESCE($3, description),
+                 version = version + 1,
+                 updated_at = NOW()
+             WHERE id = $1
+             RETURNING *
+         )
+         SELECT v.id, v.stream_id, v.title, v.description, v.duration_seconds, v.size_bytes,
+                v.processing_index, v.thumbnail_path, v.uploader_id,
+                u.display_name AS uploader_name, v.version, v.created_at, v.updated_at,
+                (SELECT d.original_video_id FROM duplicate_matches d
+                 WHERE d.video_id = v.id ORDER BY d.similarity DESC LIMIT 1) AS duplicate_of
+         FROM v
+         JOIN users u ON u.id = v.uploader_id",
+    )
+    .bind(id)
+    .bind(title)
+    .bind(&request.description)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Json(video.into()))
 }
 
-async fn like_video(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Like/unlike video {} endpoint", id),
-        purpose: "Toggle like status for video and update total like count".to_string(),
-    })
+#[derive(Deserialize)]
+struct DeleteVideoQuery {
+    mode: Option<String>,
 }
 
-async fn get_likes(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get likes for video {} endpoint", id),
-        purpose: "Get like count and current user's like status for the video".to_string(),
-    })
+impl Validate for DeleteVideoQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if !matches!(self.mode.as_deref(), None | Some("soft" | "hard")) {
+            return Err(AppError::Validation("mode must be soft or hard".to_string()));
+        }
+        Ok(())
+    }
 }
 
-async fn share_video(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Share video {} endpoint", id),
-        purpose: "Generate shareable link with expiration for video access outside stream".to_string(),
-    })
+fn video_delete_grace() -> Duration {
+    config::get().video_delete_grace
 }
 
-async fn get_shares(Path(id): Path<String>) -> Json<VideoResponse> {
-    Json(VideoResponse {
-        message: format!("Get shares for video {} endpoint", id),
-        purpose: "Get share count and sharing statistics for the video".to_string(),
+// Soft deletes (the default) hide the video until the grace period ends and
+// the sweep purges it; `mode=hard` removes it and its files right away, and
+// also works on a video that's already soft-deleted.
+async fn delete_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<DeleteVideoQuery>,
+) -> Result<StatusCode, AppError> {
+    let hard = query.mode.as_deref() == Some("hard");
+
+    let mut tx = pool.begin().await?;
+    check_video_manager(&mut *tx, id, user.user_id, hard).await?;
+
+    if !hard {
+        sqlx::query("UPDATE videos SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let (storage_path, thumbnail_path) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "DELETE FROM videos WHERE id = $1 RETURNING storage_path, thumbnail_path",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    purge_video_objects(&pool, id, storage_path, thumbnail_path).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn restore_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VideoDetails>, AppError> {
+    let mut tx = pool.begin().await?;
+    check_video_manager(&mut *tx, id, user.user_id, true).await?;
+
+    let deleted_at = sqlx::query_scalar::<_, Option<OffsetDateTime>>(
+        "SELECT deleted_at FROM videos WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Video is not deleted".to_string()))?;
+
+    // The sweep may not have run yet; the window still applies
+    if deleted_at + video_delete_grace() <= OffsetDateTime::now_utc() {
+        return Err(AppError::Gone("Restore window has passed".to_string()));
+    }
+
+    let video = sqlx::query_as::<_, VideoRow>(
+        "WITH v AS (
+             UPDATE videos SET deleted_at = NULL, updated_at = NOW()
+             WHERE id = $1
+             RETURNING *
+         )
+         SELECT v.id, v.stream_id, v.title, v.description, v.duration_seconds, v.size_bytes,
+                v.processing_index, v.thumbnail_path, v.uploader_id,
+                u.display_name AS uploader_name, v.version, v.created_at, v.updated_at,
+                (SELECT d.original_video_id FROM duplicate_matches d
+                 WHERE d.video_id = v.id ORDER BY d.similarity DESC LIMIT 1) AS duplicate_of
+         FROM v
+         JOIN users u ON u.id = v.uploader_id",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Json(video.into()))
+}
+
+/// Purge soft-deleted videos whose grace period has run out, along with
+/// their stored files. Runs for the life of the server.
+pub async fn sweep_deleted_videos(pool: PgPool) {
+    let mut interval = tokio::time::interval(DELETED_SWEEP_INTERVAL);
+    loop {
+        interval.tick().await;
+        match purge_expired_videos(&pool).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!(purged, "Purged soft-deleted videos"),
+            Err(err) => tracing::error!(error = %err, "Failed to purge soft-deleted videos"),
+        }
+    }
+}
+
+// Deletes in batches so one sweep never holds a long transaction
+async fn purge_expired_videos(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let grace_secs = video_delete_grace().as_secs_f64();
+    let mut purged = 0;
+    loop {
+        let expired = sqlx::query_as::<_, (Uuid, Option<String>, Option<String>)>(
+            "DELETE FROM videos
+             WHERE id IN (
+                 SELECT id FROM videos
+                 WHERE deleted_at < NOW() - make_interval(secs => $1)
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id, storage_path, thumbnail_path",
+        )
+        .bind(grace_secs)
+        .bind(DELETED_SWEEP_BATCH)
+        .fetch_all(pool)
+        .await?;
+
+        let count = expired.len();
+        for (id, storage_path, thumbnail_path) in expired {
+            purge_video_objects(pool, id, storage_path, thumbnail_path).await;
+        }
+        purged += count;
+        if (count as i64) < DELETED_SWEEP_BATCH {
+            return Ok(purged);
+        }
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct Rendition {
+    name: String,
+    width: i32,
+    height: i32,
+    bitrate_kbps: i32,
+}
+
+#[derive(Serialize)]
+struct VideoStream {
+    /// HLS master playlist through `/files/videos/{id}/master.m3u8`, which
+    /// signs every variant playlist and segment it references.
+    hls_master_url: String,
+    /// MPEG-DASH manifest for the same renditions, through
+    /// `/files/videos/{id}/manifest.mpd`; signed like `file_url`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dash_url: Option<String>,
+    /// The original upload through `/files/videos/{id}/stream`, signed so a
+    /// `<video>` element can fetch it without an `Authorization` header.
+    file_url: String,
+    renditions: Vec<Rendition>,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+}
+
+async fn get_video_stream(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VideoStream>, AppError> {
+    let (stream_id, processing_index, hls_master_path) =
+        fetch_video::<(Uuid, i32, Option<String>)>(&pool, id, "stream_id, processing_index, hls_master_path").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // A ready video without a master playlist means transcoding output is missing
+    match (processing_index, hls_master_path) {
+        (processing::READY, Some(_)) => {}
+        (processing::READY, None) => return Err(AppError::Internal),
+        _ => return Err(AppError::Conflict("Video is still processing".to_string())),
+    };
+
+    let renditions = sqlx::query_as::<_, Rendition>(
+        "SELECT name, width, height, bitrate_kbps
+         FROM video_renditions
+         WHERE video_id = $1
+         ORDER BY height, bitrate_kbps",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+
+    let hls_master = files::hls_master_url(id, Purpose::Stream);
+    // Built from the rendition ladder on request, so there's none without one
+    let dash_url = (!renditions.is_empty())
+        .then(|| signing::sign_path(&format!("/files/videos/{}/manifest.mpd", id), Purpose::Stream).url);
+    // Carries the viewer so the file route can still record the view
+    let file = signing::sign_path(
+        &format!("/files/videos/{}/stream?viewer={}", id, user.user_id),
+        Purpose::Stream,
+    );
+
+    Ok(Json(VideoStream {
+        hls_master_url: hls_master.url,
+        dash_url,
+        file_url: file.url,
+        renditions,
+        expires_at: hls_master.expires_at,
+    }))
+}
+
+/// Check a client-supplied `content_hash` (hex SHA-256 of the file) and
+/// return it lowercased.
+pub(crate) fn normalize_content_hash(hash: &str) -> Result<String, AppError> {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::Validation("content_hash must be a hex SHA-256 digest".to_string()));
+    }
+    Ok(hash.to_ascii_lowercase())
+}
+
+/// A live video in the stream already registered with `content_hash`,
+/// other than `except`. Served by `idx_videos_stream_content_hash`.
+pub(crate) async fn find_by_content_hash(
+    executor: impl PgExecutor<'_>,
+    stream_id: Uuid,
+    content_hash: &str,
+    except: Option<Uuid>,
+) -> Result<Option<Uuid>, AppError> {
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM videos
+         WHERE stream_id = $1 AND content_hash = $2 AND deleted_at IS NULL
+           AND processing_index <> $3 AND ($4::uuid IS NULL OR id <> $4)
+         ORDER BY created_at
+         LIMIT 1",
+    )
+    .bind(stream_id)
+    .bind(content_hash)
+    .bind(processing::REJECTED)
+    .bind(except)
+    .fetch_optional(executor)
+    .await?;
+    Ok(id)
+}
+
+/// File extension stored for an accepted upload content type.
+pub(crate) fn upload_extension(content_type: &str) -> Result<&'static str, AppError> {
+    ALLOWED_UPLOAD_TYPES
+        .iter()
+        .find(|(allowed, _)| *allowed == content_type)
+        .map(|(_, extension)| *extension)
+        .ok_or_else(|| {
+            AppError::Validation("content_type must be video/mp4, video/quicktime, or video/webm".to_string())
+        })
+}
+
+#[derive(Deserialize)]
+struct UploadUrlRequest {
+    content_type: String,
+    /// Replaces the size declared when the video was registered, checked
+    /// against the stream's quota.
+    size_bytes: Option<i64>,
+    /// Hex SHA-256 of the file; refused if the stream already has it.
+    content_hash: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct UploadUrlResponse {
+    pub(crate) upload_url: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) expires_at: OffsetDateTime,
+    /// Headers the client must send with the `PUT`.
+    #[schema(value_type = HashMap<String, String>)]
+    pub(crate) headers: HashMap<&'static str, String>,
+}
+
+async fn get_upload_url(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UploadUrlRequest>,
+) -> Result<Json<UploadUrlResponse>, AppError> {
+    let extension = upload_extension(&request.content_type)?;
+    if request.size_bytes.is_some_and(|size| size < 1) {
+        return Err(AppError::Validation("size_bytes must be positive".to_string()));
+    }
+    let content_hash = request.content_hash.as_deref().map(normalize_content_hash).transpose()?;
+
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Creator).await?;
+
+    let mut tx = pool.begin().await?;
+    // Lock the stream (for the quota) before the video, same order as uploads
+    let usage = streams::lock_stream_usage(&mut *tx, stream_id).await?;
+    let (processing_index, declared_bytes, uploaded) = lock_video::<(i32, Option<i64>, bool)>(
+        &mut *tx,
+        id,
+        "processing_index, size_bytes, uploaded_at IS NOT NULL",
+    )
+    .await?;
+
+    // The original can't be replaced once the upload is confirmed
+    if uploaded || processing_index != processing::QUEUED {
+        return Err(AppError::Conflict("Video has already been uploaded".to_string()));
+    }
+    if let Some(size_bytes) = request.size_bytes {
+        usage.check(size_bytes - declared_bytes.unwrap_or(0))?;
+    }
+    // The stream lock above serializes this check against other uploads
+    if let Some(hash) = &content_hash
+        && let Some(existing) = find_by_content_hash(&mut *tx, stream_id, hash, Some(id)).await?
+    {
+        return Err(AppError::Conflict(format!(
+            "This file is already in the stream as video {}",
+            existing
+        )));
+    }
+
+    let key = format!("raw-uploads/{}.{}", id, extension);
+    let upload = signing::storage_put(&key, Purpose::Upload).await?;
+
+    sqlx::query(
+        "UPDATE videos
+         SET storage_path = $2, size_bytes = COALESCE($3, size_bytes), content_hash = COALESCE($4, content_hash),
+             updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(&key)
+    .bind(request.size_bytes)
+    .bind(&content_hash)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(UploadUrlResponse {
+        upload_url: upload.url,
+        expires_at: upload.expires_at,
+        headers: HashMap::from([("Content-Type", request.content_type)]),
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingUpload {
+    processing_index: i32,
+    storage_path: Option<String>,
+    size_bytes: Option<i64>,
+    uploaded_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct CompletedUpload {
+    id: Uuid,
+    processing_index: i32,
+    #[sqlx(skip)]
+    status: &'static str,
+    size_bytes: Option<i64>,
+    content_type: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    uploaded_at: Option<OffsetDateTime>,
+}
+
+fn rejected_upload_error() -> AppError {
+    AppError::Validation("Uploaded file is not an MP4, QuickTime or WebM video".to_string())
+}
+
+async fn complete_upload(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CompletedUpload>, AppError> {
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Creator).await?;
+
+    let mut tx = pool.begin().await?;
+    // Lock the stream (for the quota) before the video, same order as uploads
+    let usage = streams::lock_stream_usage(&mut *tx, stream_id).await?;
+    let upload =
+        lock_video::<PendingUpload>(&mut *tx, id, "processing_index, storage_path, size_bytes, uploaded_at").await?;
+
+    if upload.processing_index == processing::REJECTED {
+        return Err(rejected_upload_error());
+    }
+
+    // Completing twice is a no-op that reports the current state
+    if upload.uploaded_at.is_none() {
+        let key = upload
+            .storage_path
+            .as_deref()
+            .ok_or_else(|| AppError::Conflict("No upload has been started for this video".to_string()))?;
+        let (meta, content_type) = match storage::head_with_content_type(key).await {
+            Ok(head) => head,
+            Err(object_store::Error::NotFound { .. }) => {
+                return Err(AppError::Conflict("Uploaded file not found in storage".to_string()));
+            }
+            Err(err) => {
+                tracing::error!(error = %err, "Storage error");
+                return Err(AppError::Internal);
+            }
+        };
+
+        // The declared Content-Type is only the client's word; check the bytes
+        let head = storage::get_range(key, 0..meta.size.min(sniff::SNIFF_BYTES) as u64)
+            .await
+            .map_err(|err| {
+                tracing::error!(error = %err, "Storage error");
+                AppError::Internal
+            })?;
+        let Some(container) = sniff::video_container(&head) else {
+            let key = key.to_string();
+            sqlx::query(
+                "UPDATE videos SET processing_index = $2, storage_path = NULL, updated_at = NOW() WHERE id = $1",
+            )
+            .bind(id)
+            .bind(processing::REJECTED)
+            .execute(&mut *tx)
+            .await?;
+            // Nothing will be processed, so the waiting first-stage job goes too
+            sqlx::query("DELETE FROM processing_jobs WHERE video_id = $1 AND status = 'awaiting_upload'")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+
+            tracing::warn!(video_id = %id, content_type = ?content_type, "Rejected upload that isn't a supported video");
+            delete_video_objects(&pool, id, vec![key]).await;
+            return Err(rejected_upload_error());
+        };
+
+        // The quota was checked against the declared size; enforce it on the real one
+        let size_bytes = meta.size as i64;
+        if usage.used_bytes - upload.size_bytes.unwrap_or(0) + size_bytes > usage.quota_bytes {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Upload exceeds the stream's storage quota of {} bytes",
+                usage.quota_bytes
+            )));
+        }
+
+        sqlx::query(
+            "UPDATE videos
+             SET size_bytes = $2, content_type = $3, uploaded_at = NOW(), updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(size_bytes)
+        .bind(container.content_type())
+        .execute(&mut *tx)
+        .await?;
+
+        // Hand the first stage to the workers; it joins the queue now
+        sqlx::query(
+            "UPDATE processing_jobs SET status = 'queued', created_at = NOW()
+             WHERE video_id = $1 AND status = 'awaiting_upload'",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let mut completed = fetch_video::<CompletedUpload>(
+        &mut *tx,
+        id,
+        "id, processing_index, size_bytes, content_type, uploaded_at",
+    )
+    .await?;
+    tx.commit().await?;
+
+    completed.status = processing::status_name(completed.processing_index);
+    Ok(Json(completed))
+}
+
+#[derive(sqlx::FromRow)]
+struct LatestJob {
+    id: Uuid,
+    status: String,
+    created_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct ProcessingStatus {
+    video_id: Uuid,
+    processing_index: i32,
+    status: &'static str,
+    job_status: String,
+    progress_percent: f64,
+    queue_position: Option<i64>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    estimated_completion: Option<OffsetDateTime>,
+}
+
+async fn get_processing_status(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ProcessingStatus>, AppError> {
+    let (stream_id, processing_index, processing_progress) =
+        fetch_video::<(Uuid, i32, Option<f64>)>(&pool, id, "stream_id, processing_index, processing_progress").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    let job = sqlx::query_as::<_, LatestJob>(
+        "SELECT id, status, created_at FROM processing_jobs
+         WHERE video_id = $1
+         ORDER BY created_at DESC
+         LIMIT 1",
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    // Jobs are picked up oldest first, so position is the count queued ahead
+    let queue_position = if job.status == "queued" {
+        let ahead = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM processing_jobs
+             WHERE status = 'queued' AND (created_at, id) < ($1, $2)",
+        )
+        .bind(job.created_at)
+        .bind(job.id)
+        .fetch_one(&pool)
+        .await?;
+        Some(ahead + 1)
+    } else {
+        None
+    };
+
+    let stage_progress = processing_progress.unwrap_or(0.0).clamp(0.0, 1.0);
+    let progress_percent = processing::progress_percent(processing_index, stage_progress);
+    let estimated_completion = match processing_index {
+        index @ (processing::QUEUED | 1..=4) => {
+            let stage = (index - 1).max(0) as usize;
+            let stage_done = if index == processing::QUEUED { 0.0 } else { stage_progress };
+
+            // Average duration of each stage over the past week of completed jobs
+            let averages = sqlx::query_as::<_, (String, f64)>(
+                "SELECT stage, EXTRACT(EPOCH FROM AVG(finished_at - started_at))::float8
+                 FROM processing_jobs
+                 WHERE status = 'completed' AND started_at IS NOT NULL
+                   AND finished_at > NOW() - INTERVAL '7 days'
+                 GROUP BY stage",
+            )
+            .fetch_all(&pool)
+            .await?;
+
+            let mut avg_stage_secs = [None; 4];
+            for (name, secs) in averages {
+                if let Some(position) = processing::STAGES.iter().position(|stage| *stage == name) {
+                    avg_stage_secs[position] = Some(secs);
+                }
+            }
+
+            let remaining = processing::estimate_remaining_secs(stage, stage_done, &avg_stage_secs);
+            remaining.map(|secs| OffsetDateTime::now_utc() + Duration::from_secs_f64(secs.max(0.0)))
+        }
+        _ => None,
+    };
+
+    Ok(Json(ProcessingStatus {
+        video_id: id,
+        processing_index,
+        status: processing::status_name(processing_index),
+        job_status: job.status,
+        progress_percent,
+        queue_position,
+        estimated_completion,
+    }))
+}
+
+#[derive(Serialize)]
+struct ProgressUpdate {
+    processing_index: i32,
+    stage: &'static str,
+    percent: f64,
+}
+
+// Turn a processing state into the SSE event to send, and whether it ends the stream
+fn progress_sse_event(processing_index: i32, progress: Option<f64>, failed: bool) -> (Event, bool) {
+    let update = ProgressUpdate {
+        processing_index,
+        stage: processing::status_name(processing_index),
+        percent: processing::progress_percent(processing_index, progress.unwrap_or(0.0)),
+    };
+    let (name, terminal) = match processing_index {
+        _ if failed => ("failed", true),
+        processing::READY | processing::DUPLICATE => ("done", true),
+        processing::REJECTED => ("failed", true),
+        _ => ("progress", false),
+    };
+    let event = Event::default().event(name).json_data(&update).unwrap_or_default();
+    (event, terminal)
+}
+
+async fn processing_events(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    // Subscribe before reading the current state so no update slips in between
+    let events = processing::subscribe();
+
+    let (stream_id, processing_index, progress, failed) = fetch_video::<(Uuid, i32, Option<f64>, bool)>(
+        &pool,
+        id,
+        "stream_id, processing_index, processing_progress,
+         COALESCE((SELECT status = 'failed' FROM processing_jobs j
+                   WHERE j.video_id = videos.id
+                   ORDER BY j.created_at DESC
+                   LIMIT 1), FALSE)",
+    )
+    .await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // Send the current state first, then changes until a terminal event
+    let initial = progress_sse_event(processing_index, progress, failed);
+    let stream = futures::stream::unfold(
+        (Some(initial), events, false),
+        move |(pending, mut events, finished)| async move {
+            if finished {
+                return None;
+            }
+            if let Some((event, terminal)) = pending {
+                return Some((Ok(event), (None, events, terminal)));
+            }
+            loop {
+                match events.recv().await {
+                    Ok(update) if update.video_id == id => {
+                        let (event, terminal) =
+                            progress_sse_event(update.processing_index, update.progress, update.failed);
+                        return Some((Ok(event), (None, events, terminal)));
+                    }
+                    // Each event carries the full state, so skipped ones don't matter
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[derive(sqlx::FromRow)]
+struct DuplicateMatchRow {
+    id: Uuid,
+    title: String,
+    uploaded_by: Uuid,
+    uploaded_at: OffsetDateTime,
+    similarity: f32,
+    method: String,
+}
+
+#[derive(Serialize)]
+struct OriginalVideo {
+    id: Uuid,
+    title: String,
+    uploaded_by: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    uploaded_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct DuplicateMatch {
+    original_video: OriginalVideo,
+    similarity: f32,
+    method: String,
+}
+
+#[derive(Serialize)]
+struct Duplicates {
+    is_duplicate: bool,
+    matches: Vec<DuplicateMatch>,
+}
+
+async fn get_duplicates(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Duplicates>, AppError> {
+    let (stream_id, processing_index) = fetch_video::<(Uuid, i32)>(&pool, id, "stream_id, processing_index").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    if processing_index != processing::DUPLICATE {
+        return Ok(Json(Duplicates {
+            is_duplicate: false,
+            matches: Vec::new(),
+        }));
+    }
+
+    // Originals may live in other streams; only list the ones the caller can see
+    let matches = sqlx::query_as::<_, DuplicateMatchRow>(
+        "SELECT v.id, v.title, v.uploader_id AS uploaded_by, v.created_at AS uploaded_at,
+                d.similarity, d.method
+         FROM duplicate_matches d
+         JOIN videos v ON v.id = d.original_video_id
+         JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $2
+         WHERE d.video_id = $1 AND v.deleted_at IS NULL
+         ORDER BY d.similarity DESC",
+    )
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(Duplicates {
+        is_duplicate: true,
+        matches: matches
+            .into_iter()
+            .map(|row| DuplicateMatch {
+                original_video: OriginalVideo {
+                    id: row.id,
+                    title: row.title,
+                    uploaded_by: row.uploaded_by,
+                    uploaded_at: row.uploaded_at,
+                },
+                similarity: row.similarity,
+                method: row.method,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct SimilarQuery {
+    limit: Option<i64>,
+}
+
+// `limit` is clamped rather than rejected
+impl Validate for SimilarQuery {}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct SimilarVideo {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    thumbnail_key: Option<String>,
+    similarity: f64,
+}
+
+#[derive(Serialize)]
+struct SimilarVideos {
+    similar_videos: Vec<SimilarVideo>,
+}
+
+async fn get_similar(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<SimilarQuery>,
+) -> Result<Json<SimilarVideos>, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_SIMILAR_LIMIT).clamp(1, MAX_SIMILAR_LIMIT);
+
+    // The vector is read back as its text form and bound as a parameter so the
+    // neighbour search below can use the HNSW index
+    let (stream_id, embedding) = fetch_video::<(Uuid, Option<String>)>(&pool, id, "stream_id, embedding::text").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    let embedding = embedding.ok_or_else(|| AppError::Conflict("Embedding not ready".to_string()))?;
+
+    let similar_videos = sqlx::query_as::<_, SimilarVideo>(
+        "SELECT v.id, v.stream_id, v.title, v.thumbnail_path AS thumbnail_key,
+                1 - (v.embedding <=> $1::vector) AS similarity
+         FROM videos v
+         JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $2
+         WHERE v.id <> $3 AND v.embedding IS NOT NULL AND v.deleted_at IS NULL
+         ORDER BY v.embedding <=> $1::vector
+         LIMIT $4",
+    )
+    .bind(embedding)
+    .bind(user.user_id)
+    .bind(id)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(SimilarVideos { similar_videos }))
+}
+
+#[derive(Deserialize)]
+struct TrimmedQuery {
+    min_confidence: Option<f32>,
+}
+
+impl Validate for TrimmedQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.min_confidence.is_some_and(|confidence| !(0.0..=1.0).contains(&confidence)) {
+            return Err(AppError::Validation("min_confidence must be between 0 and 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TrimmedClipRow {
+    id: Uuid,
+    label: String,
+    start_seconds: f64,
+    end_seconds: f64,
+    confidence: f32,
+    storage_path: String,
+}
+
+#[derive(Serialize)]
+struct TrimmedClip {
+    id: Uuid,
+    label: String,
+    start: f64,
+    end: f64,
+    confidence: f32,
+    stream_url: String,
+}
+
+#[derive(Serialize)]
+struct TrimmedClips {
+    clips: Vec<TrimmedClip>,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+}
+
+async fn get_trimmed(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<TrimmedQuery>,
+) -> Result<Json<TrimmedClips>, AppError> {
+    let min_confidence = query.min_confidence.unwrap_or(0.0);
+
+    let (stream_id, processing_index) = fetch_video::<(Uuid, i32)>(&pool, id, "stream_id, processing_index").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // Clips are cut during the final analysis stage
+    if processing_index != processing::READY {
+        return Err(AppError::Conflict("Clip generation has not completed".to_string()));
+    }
+
+    let rows = sqlx::query_as::<_, TrimmedClipRow>(
+        "SELECT id, label, start_seconds, end_seconds, confidence, storage_path
+         FROM trimmed_clips
+         WHERE video_id = $1 AND confidence >= $2
+         ORDER BY start_seconds, id",
+    )
+    .bind(id)
+    .bind(min_confidence)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut clips = Vec::with_capacity(rows.len());
+    for row in rows {
+        let stream_url = signing::storage_get(&row.storage_path, Purpose::Stream).await?.url;
+        clips.push(TrimmedClip {
+            id: row.id,
+            label: row.label,
+            start: row.start_seconds,
+            end: row.end_seconds,
+            confidence: row.confidence,
+            stream_url,
+        });
+    }
+
+    Ok(Json(TrimmedClips {
+        clips,
+        expires_at: OffsetDateTime::now_utc() + Purpose::Stream.ttl(),
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct PovRow {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    uploader_id: Uuid,
+    uploader_name: String,
+    offset_seconds: Option<f64>,
+    processing_index: i32,
+    hls_master_path: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PovVideo {
+    id: Uuid,
+    stream_id: Uuid,
+    title: String,
+    uploader: Uploader,
+    offset_seconds: Option<f64>,
+    stream_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PovGroup {
+    pov_group_id: Option<Uuid>,
+    videos: Vec<PovVideo>,
+}
+
+async fn get_pov(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PovGroup>, AppError> {
+    let (stream_id, pov_group_id) = fetch_video::<(Uuid, Option<Uuid>)>(&pool, id, "stream_id, pov_group_id").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    let Some(group_id) = pov_group_id else {
+        return Ok(Json(PovGroup {
+            pov_group_id: None,
+            videos: Vec::new(),
+        }));
+    };
+
+    let rows = sqlx::query_as::<_, PovRow>(
+        "SELECT v.id, v.stream_id, v.title, v.uploader_id, u.display_name AS uploader_name,
+                v.pov_offset_seconds AS offset_seconds, v.processing_index, v.hls_master_path
+         FROM videos v
+         JOIN users u ON u.id = v.uploader_id
+         JOIN stream_members m ON m.stream_id = v.stream_id AND m.user_id = $3
+         WHERE v.pov_group_id = $1 AND v.id <> $2 AND v.deleted_at IS NULL
+         ORDER BY v.pov_offset_seconds NULLS LAST, v.id",
+    )
+    .bind(group_id)
+    .bind(id)
+    .bind(user.user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut videos = Vec::with_capacity(rows.len());
+    for row in rows {
+        // Angles that are still processing are listed without a playable URL
+        let stream_url = match (row.processing_index, row.hls_master_path) {
+            (processing::READY, Some(_)) => Some(files::hls_master_url(row.id, Purpose::Stream).url),
+            _ => None,
+        };
+        videos.push(PovVideo {
+            id: row.id,
+            stream_id: row.stream_id,
+            title: row.title,
+            uploader: Uploader {
+                id: row.uploader_id,
+                display_name: row.uploader_name,
+            },
+            offset_seconds: row.offset_seconds,
+            stream_url,
+        });
+    }
+
+    Ok(Json(PovGroup {
+        pov_group_id: Some(group_id),
+        videos,
+    }))
+}
+
+#[derive(Deserialize)]
+struct TranscriptQuery {
+    format: Option<String>,
+    /// Language code of a translation to return instead of the original.
+    lang: Option<String>,
+}
+
+impl Validate for TranscriptQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if !matches!(self.format.as_deref(), None | Some("json" | "vtt")) {
+            return Err(AppError::Validation("format must be json or vtt".to_string()));
+        }
+        if self.lang.as_deref().is_some_and(|lang| !is_language_code(lang)) {
+            return Err(AppError::Validation("lang must be a language code such as en or pt-BR".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow)]
+struct TranscriptSegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct Transcript {
+    video_id: Uuid,
+    /// Language of `segments`.
+    language: String,
+    /// Language detected in the audio.
+    original_language: String,
+    /// Whether `segments` is a translation rather than the original.
+    translated: bool,
+    /// The original language followed by every translation.
+    available_languages: Vec<String>,
+    segments: Vec<TranscriptSegment>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TranscriptRow {
+    original_language: String,
+    translation_language: Option<String>,
+    segments: sqlx::types::Json<Vec<TranscriptSegment>>,
+    translations: Vec<String>,
+}
+
+// Language codes like `en`, `pt-BR` or `zh-Hant`
+fn is_language_code(code: &str) -> bool {
+    let mut parts = code.split('-');
+    let primary = parts.next().unwrap_or_default();
+    code.len() <= 16
+        && (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+// `HH:MM:SS.mmm`, as WebVTT cue timings require
+fn vtt_timestamp(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+fn to_webvtt(segments: &[TranscriptSegment]) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for segment in segments {
+        let text = segment.text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}\n",
+            vtt_timestamp(segment.start),
+            vtt_timestamp(segment.end),
+            text.trim()
+        ));
+    }
+    vtt
+}
+
+async fn get_transcript(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<TranscriptQuery>,
+) -> Result<Response, AppError> {
+    let as_vtt = query.format.as_deref() == Some("vtt");
+
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // Falls back to the original when there's no translation into `lang`
+    let transcript = sqlx::query_as::<_, TranscriptRow>(
+        "SELECT t.language AS original_language,
+                tr.language AS translation_language,
+                COALESCE(tr.segments, t.segments) AS segments,
+                ARRAY(SELECT language FROM transcript_translations
+                      WHERE video_id = t.video_id ORDER BY language)::text[] AS translations
+         FROM transcripts t
+         LEFT JOIN transcript_translations tr
+           ON tr.video_id = t.video_id AND lower(tr.language) = lower($2) AND lower($2) <> lower(t.language)
+         WHERE t.video_id = $1",
+    )
+    .bind(id)
+    .bind(&query.lang)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Transcript not ready".to_string()))?;
+
+    let translated = transcript.translation_language.is_some();
+    let language = transcript
+        .translation_language
+        .unwrap_or_else(|| transcript.original_language.clone());
+
+    if as_vtt {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/vtt; charset=utf-8".to_string()),
+                (header::CONTENT_LANGUAGE, language),
+            ],
+            to_webvtt(&transcript.segments),
+        )
+            .into_response());
+    }
+
+    let mut available_languages = vec![transcript.original_language.clone()];
+    available_languages.extend(transcript.translations);
+
+    Ok(Json(Transcript {
+        video_id: id,
+        language,
+        original_language: transcript.original_language,
+        translated,
+        available_languages,
+        segments: transcript.segments.0,
     })
+    .into_response())
+}
+
+#[derive(Deserialize)]
+struct TranscriptSearchQuery {
+    q: Option<String>,
+}
+
+impl TranscriptSearchQuery {
+    fn q(&self) -> &str {
+        self.q.as_deref().map(str::trim).unwrap_or_default()
+    }
+}
+
+impl Validate for TranscriptSearchQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.q().is_empty() {
+            return Err(AppError::Validation("q is required".to_string()));
+        }
+        Ok(())
+    }
+}
+
+// Segments of one video's transcript matching `q`, in playback order
+async fn search_transcript(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<TranscriptSearchQuery>,
+) -> Result<Json<Vec<TranscriptSegment>>, AppError> {
+    let q = query.q();
+
+    let (stream_id, has_transcript) = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT v.stream_id, EXISTS (SELECT 1 FROM transcripts WHERE video_id = v.id)
+         FROM videos v
+         WHERE v.id = $1 AND v.deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    if !has_transcript {
+        return Err(AppError::Conflict("Transcript not ready".to_string()));
+    }
+
+    let segments = sqlx::query_as::<_, TranscriptSegment>(
+        "SELECT start_seconds AS start, end_seconds AS \"end\", text
+         FROM transcript_segments
+         WHERE video_id = $1 AND search_vector @@ plainto_tsquery('english', $2)
+         ORDER BY position
+         LIMIT $3",
+    )
+    .bind(id)
+    .bind(q)
+    .bind(MAX_TRANSCRIPT_MATCHES)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(segments))
+}
+
+#[derive(sqlx::FromRow)]
+struct Storyboard {
+    tile_width: i32,
+    tile_height: i32,
+    columns: i32,
+    tile_count: i32,
+    interval_seconds: f64,
+}
+
+// One cue per tile, pointing at its region of the sprite via a media fragment
+fn storyboard_webvtt(id: Uuid, storyboard: &Storyboard, duration_seconds: Option<i32>) -> String {
+    let sprite_url = format!("/files/videos/{}/storyboard.jpg", id);
+    let mut vtt = String::from("WEBVTT\n");
+    for tile in 0..storyboard.tile_count {
+        let start = tile as f64 * storyboard.interval_seconds;
+        let mut end = start + storyboard.interval_seconds;
+        // The last tile usually covers less than a full interval
+        if let Some(duration) = duration_seconds
+            && tile == storyboard.tile_count - 1
+            && (duration as f64) > start
+        {
+            end = end.min(duration as f64);
+        }
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+            vtt_timestamp(start),
+            vtt_timestamp(end),
+            sprite_url,
+            tile % storyboard.columns * storyboard.tile_width,
+            tile / storyboard.columns * storyboard.tile_height,
+            storyboard.tile_width,
+            storyboard.tile_height
+        ));
+    }
+    vtt
+}
+
+async fn get_storyboard(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let (stream_id, duration_seconds) =
+        fetch_video::<(Uuid, Option<i32>)>(&pool, id, "stream_id, duration_seconds").await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    let storyboard = sqlx::query_as::<_, Storyboard>(
+        "SELECT tile_width, tile_height, columns, tile_count, interval_seconds
+         FROM storyboards WHERE video_id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Storyboard not ready".to_string()))?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/vtt; charset=utf-8")],
+        storyboard_webvtt(id, &storyboard, duration_seconds),
+    )
+        .into_response())
+}
+
+async fn get_embeddings(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let (stream_id, model, dimensions, embedding) = fetch_video::<(Uuid, Option<String>, Option<i32>, Option<String>)>(
+        &pool,
+        id,
+        "stream_id, embedding_model, vector_dims(embedding), embedding::text",
+    )
+    .await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    let (Some(dimensions), Some(embedding)) = (dimensions, embedding) else {
+        return Err(AppError::Conflict("Embeddings not computed yet".to_string()));
+    };
+
+    // pgvector's text form (`[0.1,0.2,...]`) is already a JSON array, so the
+    // vector is passed through as one chunk instead of being re-serialized
+    let model = serde_json::to_string(&model).map_err(|_| AppError::Internal)?;
+    let head = format!(
+        r#"{{"video_id":"{}","model":{},"dimensions":{},"embeddings":["#,
+        id, model, dimensions
+    );
+
+    let chunks = [
+        Bytes::from(head),
+        Bytes::from(embedding),
+        Bytes::from_static(b"]}"),
+    ];
+    let body = Body::from_stream(futures::stream::iter(chunks.map(Ok::<_, Infallible>)));
+
+    Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct TimelineMarker {
+    #[serde(rename = "type")]
+    kind: String,
+    time: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<f64>,
+    label: String,
+}
+
+#[derive(Serialize)]
+struct Timeline {
+    video_id: Uuid,
+    duration_seconds: Option<i32>,
+    markers: Vec<TimelineMarker>,
+}
+
+async fn get_timeline(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Timeline>, AppError> {
+    let (stream_id, processing_index, duration_seconds) =
+        fetch_video::<(Uuid, i32, Option<i32>)>(&pool, id, "stream_id, processing_index, duration_seconds").await?;
+
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    if processing_index != processing::READY {
+        return Err(AppError::Conflict("Video analysis has not completed".to_string()));
+    }
+
+    // Clip, highlight/chapter and transcript keyword markers, merged by time
+    let markers = sqlx::query_as::<_, TimelineMarker>(
+        "SELECT 'clip' AS kind, start_seconds AS time, end_seconds AS end_time, label
+         FROM trimmed_clips WHERE video_id = $1
+         UNION ALL
+         SELECT kind, at_seconds, NULL, label
+         FROM highlights WHERE video_id = $1
+         UNION ALL
+         SELECT 'keyword', (k ->> 'time')::float8, NULL, k ->> 'keyword'
+         FROM transcripts t, jsonb_array_elements(t.keywords) AS k
+         WHERE t.video_id = $1
+         ORDER BY time, kind",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(Timeline {
+        video_id: id,
+        duration_seconds,
+        markers,
+    }))
+}
+
+// Add the caller's reaction of this type, or remove it if already there.
+// Runs in the caller's transaction, so counts read before it commits
+// include this toggle. Returns whether the reaction is now present.
+async fn toggle_reaction(
+    conn: &mut sqlx::PgConnection,
+    video_id: Uuid,
+    user_id: Uuid,
+    reaction: &str,
+) -> Result<bool, AppError> {
+    let stream_id = video_stream_id(&mut *conn, video_id).await?;
+    streams::require_stream_role(&mut *conn, stream_id, user_id, Role::Viewer).await?;
+
+    let removed = sqlx::query("DELETE FROM video_reactions WHERE video_id = $1 AND user_id = $2 AND reaction = $3")
+        .bind(video_id)
+        .bind(user_id)
+        .bind(reaction)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected()
+        > 0;
+
+    if !removed {
+        sqlx::query(
+            "INSERT INTO video_reactions (video_id, user_id, reaction) VALUES ($1, $2, $3)
+             ON CONFLICT (video_id, reaction, user_id) DO NOTHING",
+        )
+        .bind(video_id)
+        .bind(user_id)
+        .bind(reaction)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(!removed)
+}
+
+#[derive(Serialize)]
+struct LikeState {
+    count: i64,
+    liked_by_me: bool,
+}
+
+async fn like_state(executor: impl PgExecutor<'_>, video_id: Uuid, user_id: Uuid) -> Result<LikeState, AppError> {
+    let (count, liked_by_me) = sqlx::query_as::<_, (i64, bool)>(
+        "SELECT COUNT(*), COALESCE(bool_or(user_id = $2), FALSE)
+         FROM video_reactions
+         WHERE video_id = $1 AND reaction = 'like'",
+    )
+    .bind(video_id)
+    .bind(user_id)
+    .fetch_one(executor)
+    .await?;
+    Ok(LikeState { count, liked_by_me })
+}
+
+// Kept for older clients; same as toggling the `like` reaction
+async fn like_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<LikeState>, AppError> {
+    let mut tx = pool.begin().await?;
+    let liked_by_me = toggle_reaction(&mut tx, id, user.user_id, "like").await?;
+    let count = like_state(&mut *tx, id, user.user_id).await?.count;
+    tx.commit().await?;
+
+    Ok(Json(LikeState { count, liked_by_me }))
+}
+
+async fn get_likes(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<LikeState>, AppError> {
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    Ok(Json(like_state(&pool, id, user.user_id).await?))
+}
+
+#[derive(Deserialize)]
+struct ReactRequest {
+    #[serde(rename = "type")]
+    reaction: String,
+}
+
+#[derive(Serialize)]
+struct Reactions {
+    /// Every allowed type, including those with no reactions yet.
+    counts: BTreeMap<&'static str, i64>,
+    /// The caller's own reactions.
+    mine: Vec<String>,
+}
+
+async fn reactions(executor: impl PgExecutor<'_>, video_id: Uuid, user_id: Uuid) -> Result<Reactions, AppError> {
+    let rows = sqlx::query_as::<_, (String, i64, bool)>(
+        "SELECT reaction, COUNT(*), BOOL_OR(user_id = $2)
+         FROM video_reactions
+         WHERE video_id = $1
+         GROUP BY reaction",
+    )
+    .bind(video_id)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await?;
+
+    let mut counts: BTreeMap<_, _> = REACTION_TYPES.iter().map(|reaction| (*reaction, 0)).collect();
+    let mut mine = Vec::new();
+    for (reaction, count, reacted) in rows {
+        if let Some(known) = REACTION_TYPES.iter().find(|known| **known == reaction) {
+            counts.insert(*known, count);
+        }
+        if reacted {
+            mine.push(reaction);
+        }
+    }
+    Ok(Reactions { counts, mine })
+}
+
+async fn react_to_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ReactRequest>,
+) -> Result<Json<Reactions>, AppError> {
+    if !REACTION_TYPES.contains(&request.reaction.as_str()) {
+        return Err(AppError::Validation(format!(
+            "type must be one of {}",
+            REACTION_TYPES.join(", ")
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    toggle_reaction(&mut tx, id, user.user_id, &request.reaction).await?;
+    let reactions = reactions(&mut *tx, id, user.user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(reactions))
+}
+
+async fn get_reactions(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Reactions>, AppError> {
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    Ok(Json(reactions(&pool, id, user.user_id).await?))
+}
+
+#[derive(Deserialize)]
+struct ShareRequest {
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+    max_views: Option<i32>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct Share {
+    #[serde(skip)]
+    id: Uuid,
+    code: String,
+    created_by: Uuid,
+    #[serde(with = "time::serde::rfc3339::option")]
+    expires_at: Option<OffsetDateTime>,
+    max_views: Option<i32>,
+    views_count: i32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    revoked_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct CreatedShare {
+    #[serde(flatten)]
+    share: Share,
+    share_url: String,
+}
+
+// Share links are served by the API itself (see `files::access_shared_video`)
+fn share_url(code: &str) -> String {
+    let base = &config::get().public_api_url;
+    format!("{}/share/{}", base.trim_end_matches('/'), code)
+}
+
+async fn share_video(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ShareRequest>,
+) -> Result<(StatusCode, Json<CreatedShare>), AppError> {
+    if request.expires_at <= OffsetDateTime::now_utc() {
+        return Err(AppError::Validation("expires_at must be in the future".to_string()));
+    }
+    if request.max_views.is_some_and(|max_views| max_views < 1) {
+        return Err(AppError::Validation("max_views must be at least 1".to_string()));
+    }
+
+    let stream_id = video_stream_id(&pool, id).await?;
+    streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // Codes are random, so a collision is rare; just try again with a new one
+    for _ in 0..codes::MAX_CODE_ATTEMPTS {
+        let inserted = sqlx::query_as::<_, Share>(
+            "INSERT INTO shares (code, video_id, created_by, expires_at, max_views)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, code, created_by, expires_at, max_views, views_count, revoked_at, created_at",
+        )
+        .bind(codes::generate_code(SHARE_CODE_LEN))
+        .bind(id)
+        .bind(user.user_id)
+        .bind(request.expires_at)
+        .bind(request.max_views)
+        .fetch_one(&pool)
+        .await;
+
+        match inserted {
+            Ok(share) => {
+                let share_url = share_url(&share.code);
+                return Ok((StatusCode::CREATED, Json(CreatedShare { share, share_url })));
+            }
+            Err(err) if codes::is_unique_violation(&err) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Err(AppError::Internal)
+}
+
+#[derive(Serialize)]
+struct ShareStats {
+    #[serde(flatten)]
+    page: Page<Share>,
+    total: i64,
+    total_views: i64,
+}
+
+async fn get_shares(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    ValidQuery(query): ValidQuery<PageQuery>,
+) -> Result<Json<ShareStats>, AppError> {
+    require_video_manager(&pool, id, user.user_id).await?;
+
+    let limit = query.limit();
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    let shares = sqlx::query_as::<_, Share>(
+        "SELECT id, code, created_by, expires_at, max_views, views_count, revoked_at, created_at
+         FROM shares
+         WHERE video_id = $1
+           AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4",
+    )
+    .bind(id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, share_id)| share_id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let (total, total_views) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COUNT(*), COALESCE(SUM(views_count), 0) FROM shares WHERE video_id = $1",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(ShareStats {
+        page: pagination::into_page(shares, limit, |share| (share.created_at, share.id)),
+        total,
+        total_views,
+    }))
+}
+
+// Revoking again is a no-op that keeps the original timestamp
+async fn revoke_share(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, code)): Path<(Uuid, String)>,
+) -> Result<StatusCode, AppError> {
+    let stream_id = video_stream_id(&pool, id).await?;
+    let access = streams::require_stream_role(&pool, stream_id, user.user_id, Role::Viewer).await?;
+
+    // A code from another video is reported as missing, not forbidden
+    let created_by = sqlx::query_scalar::<_, Uuid>("SELECT created_by FROM shares WHERE video_id = $1 AND code = $2")
+        .bind(id)
+        .bind(&code)
+        .fetch_one(&pool)
+        .await?;
+    if created_by != user.user_id && !access.is_owner && access.role < Role::Admin {
+        return Err(AppError::Forbidden);
+    }
+
+    sqlx::query(
+        "UPDATE shares SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE video_id = $1 AND code = $2",
+    )
+    .bind(id)
+    .bind(&code)
+    .execute(&pool)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct DailyViews {
+    /// UTC day, `YYYY-MM-DD`.
+    date: String,
+    views: i64,
+    unique_viewers: i64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct ShareView {
+    #[serde(with = "time::serde::rfc3339")]
+    viewed_at: OffsetDateTime,
+}
+
+#[derive(Serialize)]
+struct ShareAnalytics {
+    code: String,
+    views_count: i32,
+    /// Distinct viewer IPs; people behind one address count once.
+    unique_viewers: i64,
+    /// Most recent views first, at most 1000.
+    views: Vec<ShareView>,
+    daily: Vec<DailyViews>,
+}
+
+async fn get_share_analytics(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path((id, code)): Path<(Uuid, String)>,
+) -> Result<Json<ShareAnalytics>, AppError> {
+    require_video_manager(&pool, id, user.user_id).await?;
+
+    // A code from another video is reported as missing, not forbidden
+    let (share_id, views_count) = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, views_count FROM shares WHERE video_id = $1 AND code = $2",
+    )
+    .bind(id)
+    .bind(&code)
+    .fetch_one(&pool)
+    .await?;
+
+    let unique_viewers = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(DISTINCT ip_hash) FROM share_views WHERE share_id = $1",
+    )
+    .bind(share_id)
+    .fetch_one(&pool)
+    .await?;
+
+    let views = sqlx::query_as::<_, ShareView>(
+        "SELECT viewed_at FROM share_views WHERE share_id = $1 ORDER BY viewed_at DESC LIMIT $2",
+    )
+    .bind(share_id)
+    .bind(MAX_SHARE_VIEW_TIMESTAMPS)
+    .fetch_all(&pool)
+    .await?;
+
+    let daily = sqlx::query_as::<_, DailyViews>(
+        "SELECT to_char(viewed_at AT TIME ZONE 'UTC', 'YYYY-MM-DD') AS date,
+                COUNT(*) AS views,
+                COUNT(DISTINCT ip_hash) AS unique_viewers
+         FROM share_views
+         WHERE share_id = $1
+         GROUP BY 1
+         ORDER BY 1",
+    )
+    .bind(share_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ShareAnalytics {
+        code,
+        views_count,
+        unique_viewers,
+        views,
+        daily,
+    }))
 }
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
+        .route("/videos/batch", post(get_videos_batch))
         .route("/videos/:id", get(get_video))
         .route("/videos/:id", put(update_video))
         .route("/videos/:id", delete(delete_video))
+        .route("/videos/:id/restore", post(restore_video))
         .route("/videos/:id/stream", get(get_video_stream))
-        .route("/videos/:id/upload-url", post(get_upload_url))
+        .route(
+            "/videos/:id/upload-url",
+            post(get_upload_url).layer(middleware::from_fn_with_state(Group::Upload, rate_limit::enforce)),
+        )
+        .route("/videos/:id/upload-complete", post(complete_upload))
         .route("/videos/:id/processing", get(get_processing_status))
+        .route("/videos/:id/processing/events", get(processing_events))
         .route("/videos/:id/duplicates", get(get_duplicates))
         .route("/videos/:id/similar", get(get_similar))
         .route("/videos/:id/trimmed", get(get_trimmed))
         .route("/videos/:id/pov", get(get_pov))
         .route("/videos/:id/transcript", get(get_transcript))
+        .route("/videos/:id/transcript/search", get(search_transcript))
+        .route("/videos/:id/storyboard", get(get_storyboard))
         .route("/videos/:id/embeddings", get(get_embeddings))
         .route("/videos/:id/timeline", get(get_timeline))
         .route("/videos/:id/like", post(like_video))
         .route("/videos/:id/likes", get(get_likes))
-        .route("/videos/:id/share", post(share_video))
+        .route("/videos/:id/reactions", post(react_to_video))
+        .route("/videos/:id/reactions", get(get_reactions))
+        .route(
+            "/videos/:id/share",
+            post(share_video).layer(middleware::from_fn_with_state(Group::Share, rate_limit::enforce)),
+        )
         .route("/videos/:id/shares", get(get_shares))
+        .route("/videos/:id/shares/:code", delete(revoke_share))
+        .route("/videos/:id/shares/:code/analytics", get(get_share_analytics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{db_test, stream, user};
+
+    // The owner of a fresh stream, and a soft-deleted video in it
+    async fn deleted_video(pool: &PgPool) -> (AuthUser, Uuid) {
+        let user_id = user(pool, "owner").await;
+        let stream_id = stream(pool, user_id).await;
+        let video_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO videos (stream_id, uploader_id, title, processing_index, deleted_at)
+             VALUES ($1, $2, 'Deleted', $3, NOW())
+             RETURNING id",
+        )
+        .bind(stream_id)
+        .bind(user_id)
+        .bind(processing::READY)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let user = AuthUser {
+            user_id,
+            email: "owner@example.com".to_string(),
+            is_admin: false,
+        };
+        (user, video_id)
+    }
+
+    fn status<T>(result: Result<T, AppError>) -> StatusCode {
+        match result {
+            Ok(_) => StatusCode::OK,
+            Err(err) => err.into_response().status(),
+        }
+    }
+
+    db_test! {
+        async fn deleted_video_cannot_be_shared(pool: PgPool) {
+            let (user, video_id) = deleted_video(&pool).await;
+            let request = ShareRequest {
+                expires_at: OffsetDateTime::now_utc() + Duration::from_secs(3600),
+                max_views: None,
+            };
+
+            let shared = share_video(State(pool.clone()), user, Path(video_id), Json(request)).await;
+            assert_eq!(status(shared), StatusCode::NOT_FOUND);
+
+            let shares = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM shares WHERE video_id = $1")
+                .bind(video_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(shares, 0);
+        }
+
+        async fn deleted_video_has_no_transcript(pool: PgPool) {
+            let (user, video_id) = deleted_video(&pool).await;
+            sqlx::query("INSERT INTO transcripts (video_id, language, full_text) VALUES ($1, 'en', 'hello')")
+                .bind(video_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let query = TranscriptQuery { format: None, lang: None };
+            let transcript = get_transcript(State(pool.clone()), user, Path(video_id), ValidQuery(query)).await;
+            assert_eq!(status(transcript), StatusCode::NOT_FOUND);
+        }
+    }
 }
@@ -0,0 +1,501 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, put},
+    Router,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    auth::{AdminUser, AuthUser},
+    error::AppError,
+    pagination::{self, Page},
+    query::{Validate, ValidQuery},
+    streams::{self, Role},
+};
+
+const MAX_URL_LEN: usize = 2048;
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DELIVERY_BATCH: i64 = 20;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+// A claimed delivery is hidden from other instances for this long
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+// After this many failed attempts a delivery is given up on
+const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+// Retry delays double from the base, up to the cap
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+// Stored error messages are cut to this many characters
+const MAX_ERROR_LEN: usize = 500;
+const DELIVERY_STATUSES: [&str; 3] = ["pending", "delivered", "failed"];
+
+// Receivers are called with redirects disabled so a delivery can't be bounced
+// somewhere the owner didn't configure, and through `PublicResolver` so a
+// hostname can't be pointed at an internal address after it was registered
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .expect("webhook HTTP client builds")
+});
+
+/// Whether the server may send webhooks to `ip`: not loopback, private,
+/// link-local, unspecified, multicast or otherwise internal.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_broadcast()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ip(IpAddr::V4(mapped)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+#[derive(Debug)]
+struct BlockedAddress(IpAddr);
+
+impl std::fmt::Display for BlockedAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Webhook host resolves to a non-public address ({})", self.0)
+    }
+}
+
+impl std::error::Error for BlockedAddress {}
+
+// The addresses for `host`, refusing the lot if any of them is internal
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    if let Some(blocked) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(Box::new(BlockedAddress(blocked.ip())));
+    }
+    Ok(addrs)
+}
+
+// Used for every connection the delivery client makes, so the address that
+// is checked is the one that gets connected to
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+// IP literals never reach the resolver, so they're checked here
+fn literal_host_allowed(url: &reqwest::Url) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => true,
+    }
+}
+
+#[derive(Deserialize)]
+struct WebhookRequest {
+    url: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct Webhook {
+    stream_id: Uuid,
+    url: String,
+    /// Key for the `X-Clipstream-Signature` HMAC; receivers verify with it.
+    secret: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+async fn validate_url(url: &str) -> Result<String, AppError> {
+    let invalid = || AppError::Validation("url must be an absolute http or https URL".to_string());
+    let internal = || AppError::Validation("url must not point to a private or local address".to_string());
+
+    let url = url.trim();
+    if url.len() > MAX_URL_LEN {
+        return Err(invalid());
+    }
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    let Some(host) = parsed.host_str() else {
+        return Err(invalid());
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid());
+    }
+
+    // Checked again on every delivery, since DNS can change after this
+    if !literal_host_allowed(&parsed) {
+        return Err(internal());
+    }
+    let port = parsed.port_or_known_default().unwrap_or(0);
+    match resolve_public(host.trim_start_matches('[').trim_end_matches(']'), port).await {
+        Ok(addrs) if !addrs.is_empty() => Ok(parsed.to_string()),
+        Ok(_) => Err(AppError::Validation("url host could not be resolved".to_string())),
+        Err(err) if err.is::<BlockedAddress>() => Err(internal()),
+        Err(_) => Err(AppError::Validation("url host could not be resolved".to_string())),
+    }
+}
+
+fn generate_secret() -> String {
+    format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()))
+}
+
+async fn require_stream_owner(pool: &PgPool, stream_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    let access = streams::require_stream_role(pool, stream_id, user_id, Role::Viewer).await?;
+    if !access.is_owner {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+// The secret is generated once and kept when the URL changes, so receivers
+// don't have to be reconfigured
+async fn set_webhook(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<WebhookRequest>,
+) -> Result<Json<Webhook>, AppError> {
+    let url = validate_url(&request.url).await?;
+    require_stream_owner(&pool, id, user.user_id).await?;
+
+    let webhook = sqlx::query_as::<_, Webhook>(
+        "INSERT INTO stream_webhooks (stream_id, url, secret)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (stream_id) DO UPDATE SET url = EXCLUDED.url, updated_at = NOW()
+         RETURNING stream_id, url, secret, created_at, updated_at",
+    )
+    .bind(id)
+    .bind(url)
+    .bind(generate_secret())
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(webhook))
+}
+
+async fn delete_webhook(
+    State(pool): State<PgPool>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    require_stream_owner(&pool, id, user.user_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let removed = sqlx::query("DELETE FROM stream_webhooks WHERE stream_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    if removed == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    // Nowhere left to send them
+    sqlx::query(
+        "UPDATE webhook_deliveries
+         SET status = 'failed', last_error = 'Webhook removed'
+         WHERE stream_id = $1 AND status = 'pending'",
+    )
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(sqlx::FromRow)]
+struct DueDelivery {
+    id: Uuid,
+    event: String,
+    payload: String,
+    attempts: i32,
+    url: String,
+    secret: String,
+}
+
+/// Send queued webhook deliveries (queued by the database when a video
+/// finishes or fails processing), retrying failures with backoff. Runs for
+/// the life of the server.
+pub async fn deliver_pending(pool: PgPool) {
+    let mut interval = tokio::time::interval(DELIVERY_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(err) = deliver_due(&pool).await {
+            tracing::error!(error = %err, "Failed to deliver webhooks");
+        }
+    }
+}
+
+async fn deliver_due(pool: &PgPool) -> Result<(), sqlx::Error> {
+    loop {
+        // Claiming pushes next_attempt_at out by the lease, so a crash mid-send
+        // just means the delivery is retried later
+        let due = sqlx::query_as::<_, DueDelivery>(
+            "UPDATE webhook_deliveries d
+             SET next_attempt_at = NOW() + make_interval(secs => $1)
+             FROM stream_webhooks w
+             WHERE w.stream_id = d.stream_id
+               AND d.id IN (
+                   SELECT id FROM webhook_deliveries
+                   WHERE status = 'pending' AND next_attempt_at <= NOW()
+                   ORDER BY next_attempt_at
+                   LIMIT $2
+                   FOR UPDATE SKIP LOCKED
+               )
+             RETURNING d.id, d.event, d.payload::text AS payload, d.attempts, w.url, w.secret",
+        )
+        .bind(CLAIM_LEASE.as_secs_f64())
+        .bind(DELIVERY_BATCH)
+        .fetch_all(pool)
+        .await?;
+
+        let count = due.len();
+        futures::future::join_all(due.into_iter().map(|delivery| async move {
+            let outcome = send(&delivery).await;
+            record_attempt(pool, &delivery, outcome).await
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+        if (count as i64) < DELIVERY_BATCH {
+            return Ok(());
+        }
+    }
+}
+
+// `sha256=<hex>` HMAC of the exact request body, keyed by the stream's secret.
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// The response status if the receiver answered, and an error unless it was 2xx
+async fn send(delivery: &DueDelivery) -> (Option<i32>, Option<String>) {
+    // Hooks saved before addresses were checked are refused here too
+    if let Ok(url) = reqwest::Url::parse(&delivery.url)
+        && !literal_host_allowed(&url)
+    {
+        return (None, Some("Webhook URL points to a non-public address".to_string()));
+    }
+
+    let response = HTTP_CLIENT
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Clipstream-Event", &delivery.event)
+        .header("X-Clipstream-Delivery", delivery.id.to_string())
+        .header("X-Clipstream-Signature", signature(&delivery.secret, delivery.payload.as_bytes()))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16() as i32), None),
+        Ok(response) => {
+            let status = response.status();
+            (Some(status.as_u16() as i32), Some(format!("Receiver responded with {}", status)))
+        }
+        Err(err) => (None, Some(err.to_string())),
+    }
+}
+
+fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_RETRY_DELAY.saturating_mul(1 << exponent).min(MAX_RETRY_DELAY)
+}
+
+async fn record_attempt(
+    pool: &PgPool,
+    delivery: &DueDelivery,
+    (status_code, error): (Option<i32>, Option<String>),
+) -> Result<(), sqlx::Error> {
+    let attempts = delivery.attempts + 1;
+    let Some(error) = error else {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = 'delivered', attempts = $2, last_status_code = $3, last_error = NULL,
+                 delivered_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(attempts)
+        .bind(status_code)
+        .execute(pool)
+        .await?;
+        return Ok(());
+    };
+
+    let status = if attempts >= MAX_DELIVERY_ATTEMPTS { "failed" } else { "pending" };
+    if status == "failed" {
+        tracing::warn!(delivery_id = %delivery.id, url = %delivery.url, error = %error, "Giving up on webhook delivery");
+    }
+    sqlx::query(
+        "UPDATE webhook_deliveries
+         SET status = $2, attempts = $3, last_status_code = $4, last_error = $5,
+             next_attempt_at = NOW() + make_interval(secs => $6)
+         WHERE id = $1",
+    )
+    .bind(delivery.id)
+    .bind(status)
+    .bind(attempts)
+    .bind(status_code)
+    .bind(error.chars().take(MAX_ERROR_LEN).collect::<String>())
+    .bind(retry_delay(attempts).as_secs_f64())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct DeliveriesQuery {
+    /// `pending`, `delivered` or `failed`.
+    status: Option<String>,
+    stream_id: Option<Uuid>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+struct WebhookDelivery {
+    id: Uuid,
+    stream_id: Uuid,
+    video_id: Uuid,
+    event: String,
+    url: Option<String>,
+    status: String,
+    attempts: i32,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    next_attempt_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    delivered_at: Option<OffsetDateTime>,
+}
+
+impl Validate for DeliveriesQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(status) = self.status.as_deref()
+            && !DELIVERY_STATUSES.contains(&status)
+        {
+            return Err(AppError::Validation(
+                "status must be pending, delivered or failed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Newest first, for admins chasing failing receivers
+async fn list_deliveries(
+    State(pool): State<PgPool>,
+    _admin: AdminUser,
+    ValidQuery(query): ValidQuery<DeliveriesQuery>,
+) -> Result<Json<Page<WebhookDelivery>>, AppError> {
+    let limit = pagination::page_limit(query.limit);
+    let cursor = query.cursor.as_deref().map(pagination::decode_cursor).transpose()?;
+
+    let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+        "SELECT d.id, d.stream_id, d.video_id, d.event, w.url, d.status, d.attempts,
+                d.last_status_code, d.last_error, d.next_attempt_at, d.created_at, d.delivered_at
+         FROM webhook_deliveries d
+         LEFT JOIN stream_webhooks w ON w.stream_id = d.stream_id
+         WHERE ($1::text IS NULL OR d.status = $1)
+           AND ($2::uuid IS NULL OR d.stream_id = $2)
+           AND ($3::timestamptz IS NULL OR (d.created_at, d.id) < ($3, $4))
+         ORDER BY d.created_at DESC, d.id DESC
+         LIMIT $5",
+    )
+    .bind(&query.status)
+    .bind(query.stream_id)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(pagination::into_page(deliveries, limit, |delivery| (delivery.created_at, delivery.id))))
+}
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/streams/:id/webhook", put(set_webhook).delete(delete_webhook))
+        .route("/admin/webhooks/deliveries", get(list_deliveries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "224.0.0.1",
+            "100.64.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "ff02::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be blocked", ip);
+        }
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} should be allowed", ip);
+        }
+    }
+
+    #[test]
+    fn literal_internal_hosts_are_refused() {
+        for url in ["http://127.0.0.1/hook", "http://169.254.169.254/latest", "http://[::1]:8080/", "http://10.0.0.5"] {
+            assert!(!literal_host_allowed(&reqwest::Url::parse(url).unwrap()), "{}", url);
+        }
+        assert!(literal_host_allowed(&reqwest::Url::parse("https://hooks.example.com/x").unwrap()));
+        assert!(literal_host_allowed(&reqwest::Url::parse("https://93.184.216.34/x").unwrap()));
+    }
+}